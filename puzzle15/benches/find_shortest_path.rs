@@ -1,6 +1,6 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 // use std::hint::black_box;
-use puzzle15::{find_shortest_path, find_shortest_path_inefficient, GameState, Move}; // Replace `your_crate` with your crate name
+use puzzle15::{find_shortest_path, find_shortest_path_astar, find_shortest_path_idastar, GameState, Move}; // Replace `your_crate` with your crate name
 
 
 // fn fibonacci(n: u64) -> u64 {
@@ -18,8 +18,37 @@ use puzzle15::{find_shortest_path, find_shortest_path_inefficient, GameState, Mo
 // criterion_group!(benches, criterion_benchmark);
 // criterion_main!(benches);
 
+// same as `find_shortest_path`, but without remembering which states were already discovered,
+// as a baseline to compare against
+fn find_shortest_path_inefficient(from: GameState, to: GameState) -> Vec<Move> {
+    if from == to { return vec![]; }
+
+    let mut paths: Vec::<Vec<Move>> = vec![vec![]];
+
+    for _i in 1..=1000 {
+        let mut new_paths: Vec::<Vec<Move>> = vec![];
+        for path in paths {
+            for mv in Move::ALL {
+                let mut curr_state = from.clone();
+                curr_state.perform_moves(&path);
+                let mut new_path = path.clone();
+
+                if curr_state.perform_move(mv) {
+                    new_path.push(mv);
+                    if curr_state == to { return new_path; }
+                    new_paths.push(new_path);
+                }
+            }
+        }
+
+        paths = new_paths;
+    }
+
+    panic!("Did not find any valid path of any valid length");
+}
+
 fn benchmark_shortest_path(c: &mut Criterion) {
-    let test_cases = vec![
+    let test_cases = [
         vec![Move::TopToBottom, Move::TopToBottom, Move::TopToBottom],
         vec![Move::TopToBottom, Move::LeftToRight, Move::BottomToTop],
         vec![
@@ -51,8 +80,54 @@ fn benchmark_shortest_path(c: &mut Criterion) {
                 });
             },
         );
+
+        // only built with `--features rayon`. The layers of these short scrambles are small, so
+        // the thread overhead usually outweighs the parallel expansion here.
+        #[cfg(feature = "rayon")]
+        c.bench_function(
+            &format!("find_shortest_path_parallel - Test Case {}", i + 1),
+            |b| {
+                b.iter(|| {
+                    let result = puzzle15::find_shortest_path_parallel(GameState::default(), state.clone());
+                    assert_eq!(result, *expected_moves);
+                });
+            },
+        );
+    }
+}
+
+// the number of random moves of the scrambles in benchmark_solver_scaling, and the seed they are
+// drawn with, so that every run solves the same states
+const SCRAMBLE_DEPTHS: [usize; 4] = [5, 10, 15, 20];
+const SCRAMBLE_SEED: u64 = 42;
+
+type Solver = fn(GameState, GameState) -> Option<Vec<Move>>;
+
+fn benchmark_solver_scaling(c: &mut Criterion) {
+    let solvers: [(&str, Solver); 3] = [
+        ("bfs", |from, to| Some(find_shortest_path(from, to))),
+        ("astar", find_shortest_path_astar),
+        ("idastar", find_shortest_path_idastar),
+    ];
+
+    let mut group = c.benchmark_group("solver scaling");
+    // the breadth-first search takes seconds on the deepest scramble
+    group.sample_size(10);
+    for depth in SCRAMBLE_DEPTHS {
+        let mut state = GameState::default();
+        state.scramble_seeded(depth, SCRAMBLE_SEED);
+
+        for (name, solver) in solvers {
+            group.bench_with_input(BenchmarkId::new(name, depth), &state, |b, state| {
+                b.iter(|| {
+                    let result = solver(state.clone(), GameState::default()).unwrap();
+                    assert!(state.verify_solution(&result));
+                });
+            });
+        }
     }
+    group.finish();
 }
 
-criterion_group!(benches, benchmark_shortest_path);
+criterion_group!(benches, benchmark_shortest_path, benchmark_solver_scaling);
 criterion_main!(benches);
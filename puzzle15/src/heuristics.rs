@@ -0,0 +1,152 @@
+//! Heuristics that estimate how many moves are needed to reach a goal state, for use by the
+//! informed solvers.
+
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::GameState;
+
+/// An additive pattern database: the tiles are split into disjoint groups, and for every group
+/// the minimal number of moves of that group's tiles needed to put them into their goal
+/// positions is precomputed for every possible placement of the group's tiles.
+/// Tiles that are not part of any group are estimated with their Manhattan distance.
+#[derive(Debug, Clone)]
+pub struct PatternDatabase {
+    goal: GameState,
+    groups: Vec<Vec<u8>>,
+    tables: Vec<Vec<u8>>,
+}
+
+impl PatternDatabase {
+    /// Builds a database for the given goal state and groups of tiles.
+    /// Memory and build time grow quickly with the group size: a group of k tiles on a 4x4
+    /// board needs 16^(k+1) bytes while building, so groups should have at most 5 tiles.
+    ///
+    /// Panics if a tile is not part of the goal state or appears in more than one group.
+    pub fn new(goal: &GameState, groups: &[&[u8]]) -> Self {
+        let mut seen = vec![false; goal.size() * goal.size()];
+        for tile in groups.iter().flat_map(|group| group.iter()) {
+            let tile = *tile as usize;
+            assert!(tile > 0 && tile < seen.len(), "tile {tile} is not part of the goal state");
+            assert!(!seen[tile], "tile {tile} appears in more than one group");
+            seen[tile] = true;
+        }
+
+        let tables = groups.iter().map(|group| build_table(goal, group)).collect();
+        Self {
+            goal: goal.clone(),
+            groups: groups.iter().map(|group| group.to_vec()).collect(),
+            tables,
+        }
+    }
+
+    /// Builds the standard 5-5-5 partition of the 15-puzzle: tiles 1-5, 6-10 and 11-15.
+    pub fn new_555(goal: &GameState) -> Self {
+        Self::new(goal, &[&[1, 2, 3, 4, 5], &[6, 7, 8, 9, 10], &[11, 12, 13, 14, 15]])
+    }
+
+    /// Returns the goal state this database was built for.
+    pub fn goal(&self) -> &GameState {
+        &self.goal
+    }
+
+    /// Returns a lower bound on the number of moves needed to get from the state to the goal.
+    pub fn estimate(&self, state: &GameState) -> u32 {
+        let positions = cell_positions(state);
+        let goal_positions = cell_positions(&self.goal);
+        let cells = positions.len();
+
+        let mut in_group = vec![false; cells];
+        let mut estimate = 0;
+        for (group, table) in self.groups.iter().zip(&self.tables) {
+            let group_positions: Vec<usize> = group.iter().map(|tile| positions[*tile as usize]).collect();
+            estimate += table[encode(&group_positions, cells)] as u32;
+            for tile in group {
+                in_group[*tile as usize] = true;
+            }
+        }
+
+        // the remaining tiles are never moved by the groups above, so their Manhattan distance
+        // can be added without overestimating
+        let size = state.size();
+        for tile in 1..cells {
+            if in_group[tile] { continue; }
+            let (x, y) = (positions[tile] % size, positions[tile] / size);
+            let (goal_x, goal_y) = (goal_positions[tile] % size, goal_positions[tile] / size);
+            estimate += (x.abs_diff(goal_x) + y.abs_diff(goal_y)) as u32;
+        }
+        estimate
+    }
+}
+
+// returns the row-major cell index of every tile, with the empty position at index 0
+fn cell_positions(state: &GameState) -> Vec<usize> {
+    let size = state.size();
+    let mut positions = vec![0; size * size];
+    for x in 0..size {
+        for y in 0..size {
+            positions[state.board[x][y].unwrap_or(0) as usize] = y * size + x;
+        }
+    }
+    positions
+}
+
+// packs a list of cell indices into a single index, one digit per cell
+fn encode(positions: &[usize], cells: usize) -> usize {
+    positions.iter().rev().fold(0, |index, position| index * cells + position)
+}
+
+// computes the minimal number of moves of the group's tiles needed to reach the goal, for every
+// placement of the group's tiles. Runs a 0-1 BFS backwards from the goal over (tile positions,
+// empty position), where moving a tile outside the group is free.
+fn build_table(goal: &GameState, group: &[u8]) -> Vec<u8> {
+    let size = goal.size();
+    let cells = size * size;
+    let goal_positions = cell_positions(goal);
+
+    // the empty position is the lowest digit of a search index
+    let mut start: Vec<usize> = vec![goal_positions[0]];
+    start.extend(group.iter().map(|tile| goal_positions[*tile as usize]));
+
+    let mut distances = vec![u8::MAX; cells.pow(group.len() as u32 + 1)];
+    let mut queue = VecDeque::new();
+    let start = encode(&start, cells);
+    distances[start] = 0;
+    queue.push_back(start);
+
+    let mut positions = vec![0; group.len() + 1];
+    while let Some(index) = queue.pop_front() {
+        let distance = distances[index];
+        let mut rest = index;
+        for position in positions.iter_mut() {
+            *position = rest % cells;
+            rest /= cells;
+        }
+
+        let blank = positions[0];
+        let (x, y) = (blank % size, blank / size);
+        let mut neighbors = vec![];
+        if x > 0 { neighbors.push(blank - 1); }
+        if x + 1 < size { neighbors.push(blank + 1); }
+        if y > 0 { neighbors.push(blank - size); }
+        if y + 1 < size { neighbors.push(blank + size); }
+
+        for neighbor in neighbors {
+            let mut next = positions.clone();
+            next[0] = neighbor;
+            let moved_tile = next[1..].iter().position(|position| *position == neighbor);
+            if let Some(i) = moved_tile { next[i + 1] = blank; }
+
+            let next_index = encode(&next, cells);
+            let next_distance = if moved_tile.is_some() { distance + 1 } else { distance };
+            if next_distance < distances[next_index] {
+                distances[next_index] = next_distance;
+                if moved_tile.is_some() { queue.push_back(next_index); } else { queue.push_front(next_index); }
+            }
+        }
+    }
+
+    // the database doesn't know where the empty position is, so take the best case
+    distances.chunks(cells).map(|chunk| *chunk.iter().min().unwrap()).collect()
+}
@@ -1,14 +1,39 @@
-use std::vec;
-use std::collections::HashMap;
-use std::time::Instant;
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::collections::{BTreeMap, BinaryHeap, VecDeque};
+use core::cmp::Reverse;
+
+// the solvers only need some set and map; without std they fall back to the ordered collections
+#[cfg(feature = "std")]
+use std::collections::{HashMap as Map, HashSet as Set};
+#[cfg(not(feature = "std"))]
+use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+
+pub mod heuristics;
+
+use heuristics::PatternDatabase;
 
 /// Holds information about which tile is in which position.
 /// Should be fairly compact and easy to copy.
+/// Tiles are numbered (`GameState<u8>`, which is what `GameState` stands for) unless another type
+/// is given, e.g. letters for a themed puzzle.
 #[derive(Debug, Clone)]
-pub struct GameState {
-    board: Vec<Vec<Option<u8>>>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameState<T = u8> {
+    board: Vec<Vec<Option<T>>>
 }
 
+/// What can be used as a tile. Tiles only need to be told apart, see `GameState::from_rows`.
+pub trait Tile: Clone + Eq + core::hash::Hash {}
+
+impl<T: Clone + Eq + core::hash::Hash> Tile for T {}
+
 /// Creates the default position of tiles, starting with 1 in the top left corner.
 impl Default for GameState {
     fn default() -> Self {
@@ -17,90 +42,213 @@ impl Default for GameState {
             vec![Some(2), Some(6), Some(10), Some(14)],
             vec![Some(3), Some(7), Some(11), Some(15)],
             vec![Some(4), Some(8), Some(12), None]];
-        return Self{board : x};
+        Self{board : x}
     }
 }
 
 /// Generates a human-readable representation of the game state.
-impl std::fmt::Display for GameState {
+/// The alternate form (`{:#}`) draws the grid with Unicode box-drawing characters.
+impl core::fmt::Display for GameState {
 
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut str = String::new();
-        for x in 0..4 {
-            for y in 0..4 {
-                match self.board[y][x] {
-                    Some (x) =>  str.push_str(&format!("| {:>2} ", {x})),
-                    None => str.push_str("|    ")
-                }                        
-            }
-            str.push_str("|\n");
-        }
-        write!(f, "{}", str)
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() { return write!(f, "{}", self.to_box_string()); }
+        write!(f, "{}", self.to_string_with(&DisplayConfig::default()))
     } 
 }
 
+/// Options for `GameState::to_string_with`. The default produces the same output as `Display`.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct DisplayConfig {
+    /// Shown for the empty position, e.g. "0" or "x". Empty by default.
+    pub blank: String,
+}
+
 /// Checks whether two game states are the same,.
-impl PartialEq for GameState {
+impl<T: Tile> PartialEq for GameState<T> {
     // game states are equal when 2 boards have the exact same numbers at every board location
     fn eq(&self, other: &Self) -> bool {
-        for i in 0..4 {
-            for j in 0..4 {
+        if self.size() != other.size() { return false; }
+        for i in 0..self.size() {
+            for j in 0..self.size() {
                 if self.board[i][j] != other.board[i][j] {return false;}
             }
         }
 
-        return true;
+        true
     }
 }
 
 /// Feel free to ignore this. (but do not remove)
-impl Eq for GameState {}
+impl<T: Tile> Eq for GameState<T> {}
 
-impl GameState {
-    /// Updates a position with a new tile.
-    pub fn set(&mut self, x: u8, y: u8, tile: Option<u8>) {
-        self.board[x as usize][y as usize] = tile; 
+/// Hashes the board, consistent with the equality check above.
+impl<T: Tile> core::hash::Hash for GameState<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
     }
+}
 
-    /// Returns the tile at position x,y.
-    pub fn get(&self, x: u8, y: u8) -> Option<u8> {
-        *self.board.get(x as usize).unwrap().get(y as usize).unwrap()
+/// Orders game states by their tiles read row by row, with 0 for the empty position.
+impl Ord for GameState {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.flat().cmp(&other.flat())
     }
+}
 
-    /// Returns false if there is a duplicate tile in this game state.
-    pub fn all_tiles_unique(&self) -> bool {
-        // there are 15 valid numbers; if we have seen number i so far on the board, i is set to 1. None is at loc 0.
-        let mut tile_tracker: [i32; 16] = [0; 16];
-
-        for i in 0..4 {
-            for j in 0..4 {
-                match self.board[i][j] {
-                    Some (x) => 
-                        if x > 15 || x < 1 {
-                            return false; // Invalid Board: Certain numbers out of range.
-                        }
-                        else if tile_tracker[(x) as usize] >= 1 {return false} // there is a duplicate
-                        else {tile_tracker[(x) as usize] = 1;} // this is a number we haven't seen before; there is not a duplicate so far
-                    ,
-                    None => {
-                        if tile_tracker[0] >= 1 {return false} // there is a duplicate
-                        else {tile_tracker[0] = 1;} // this is a number we haven't seen before; there is not a duplicate so far
-                    },
-                }
+impl PartialOrd for GameState {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The error returned when a string does not describe a valid game state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseGameStateError;
+
+impl core::fmt::Display for ParseGameStateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid game state")
+    }
+}
+
+impl core::error::Error for ParseGameStateError {}
+
+/// Describes which rule a game state breaks, see `GameState::validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The tile appears more than once.
+    DuplicateTile(u8),
+    /// There is no empty position.
+    NoBlank,
+    /// There is more than one empty position.
+    MultipleBlanks,
+    /// The tile is 0 or larger than the board allows.
+    TileOutOfRange(u8),
+    /// The board is not square, or too small.
+    WrongDimensions,
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationError::DuplicateTile(tile) => write!(f, "tile {tile} appears more than once"),
+            ValidationError::NoBlank => write!(f, "there is no empty position"),
+            ValidationError::MultipleBlanks => write!(f, "there is more than one empty position"),
+            ValidationError::TileOutOfRange(tile) => write!(f, "tile {tile} is out of range"),
+            ValidationError::WrongDimensions => write!(f, "the board is not square"),
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+/// The error returned when a position is not on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfBounds {
+    pub x: u8,
+    pub y: u8,
+}
+
+impl core::fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "position ({}, {}) is not on the board", self.x, self.y)
+    }
+}
+
+impl core::error::Error for OutOfBounds {}
+
+/// Parses a 4x4 game state in the format produced by `Display`, see `GameState::parse_grid`.
+impl core::str::FromStr for GameState {
+    type Err = ParseGameStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GameState::parse_grid(s).ok_or(ParseGameStateError)
+    }
+}
+
+/// Parses a 4x4 game state in the format produced by `Display`, see `GameState::parse_grid`.
+impl TryFrom<&str> for GameState {
+    type Error = ParseGameStateError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Builds a board from ((x, y), tile) pairs, starting from a board without any tiles. The board
+/// is just large enough to contain every position; later pairs overwrite earlier ones.
+/// The result is not validated, see `validate`.
+impl FromIterator<((u8, u8), Option<u8>)> for GameState {
+    fn from_iter<I: IntoIterator<Item = ((u8, u8), Option<u8>)>>(iter: I) -> Self {
+        let pairs: Vec<((u8, u8), Option<u8>)> = iter.into_iter().collect();
+        let size = pairs.iter().map(|((x, y), _)| *x.max(y) as usize + 1).max().unwrap_or(0);
+        let mut state = GameState{board: vec![vec![None; size]; size]};
+        // set would check the invariants of the half-built board
+        for ((x, y), tile) in pairs {
+            state.board[x as usize][y as usize] = tile;
+        }
+        state
+    }
+}
+
+impl<T: Tile> GameState<T> {
+    /// Creates a board from rows of tiles, so `rows[y][x]` is the tile at position x,y.
+    /// Returns None if the board is not square, has more or less than one empty position, or
+    /// contains a tile more than once.
+    pub fn from_rows(rows: Vec<Vec<Option<T>>>) -> Option<Self> {
+        let size = rows.len();
+        if size < 2 || rows.iter().any(|row| row.len() != size) { return None; }
+
+        let mut board = vec![vec![None; size]; size];
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, tile) in row.into_iter().enumerate() {
+                board[x][y] = tile;
             }
         }
-        return true;
+        let state = GameState{board};
 
+        let tiles: Vec<&T> = state.row_major_tiles().flatten().collect();
+        let unique = tiles.iter().enumerate().all(|(i, tile)| !tiles[..i].contains(tile));
+        if tiles.len() == size * size - 1 && unique {Some(state)} else {None}
     }
 
-    // returns the (x, y) location of the empty spot on the board
-    fn empty_loc(&mut self) -> (u8, u8) {
-        for i in 0..4 {
-            for j in 0..4 {
-                if self.board[i][j] == None { return (i as u8, j as u8) } 
+    /// Numbers the tiles by their position in the goal state, read row by row: the first tile of
+    /// the goal becomes 1, the second 2, and so on. Numbered boards can use all of the solvers
+    /// and heuristics. Returns None if the boards have different sizes or different tiles.
+    pub fn relabel(&self, goal: &GameState<T>) -> Option<GameState> {
+        if self.size() != goal.size() { return None; }
+
+        let goal_tiles: Vec<&T> = goal.row_major_tiles().flatten().collect();
+        let mut tiles = vec![];
+        for tile in self.row_major_tiles() {
+            match tile {
+                Some(tile) => tiles.push(goal_tiles.iter().position(|goal_tile| *goal_tile == tile)? as u8 + 1),
+                None => tiles.push(0),
             }
         }
-        panic!("Invalid Board: There are no empty positions on the board.")
+        GameState::from_flat(self.size(), &tiles)
+    }
+
+    // returns all tiles in row-major order
+    fn row_major_tiles(&self) -> impl Iterator<Item = Option<&T>> + '_ {
+        let size = self.size();
+        (0..size).flat_map(move |y| (0..size).map(move |x| self.board[x][y].as_ref()))
+    }
+
+    // returns the number of rows (and columns) of the board
+    fn size(&self) -> usize {
+        self.board.len()
+    }
+
+    /// Returns the tile at position x,y.
+    pub fn get(&self, x: u8, y: u8) -> Option<T> {
+        self.board.get(x as usize).unwrap().get(y as usize).unwrap().clone()
+    }
+
+    /// Updates a position with a new tile.
+    pub fn set(&mut self, x: u8, y: u8, tile: Option<T>) {
+        self.board[x as usize][y as usize] = tile; 
+        self.debug_assert_invariants();
     }
 
     /// Swaps the tile from (x1,y1) with the tile from (x2,y2)
@@ -108,21 +256,86 @@ impl GameState {
         let x1 = x1 as usize; let y1 = y1 as usize;
         let x2 = x2 as usize; let y2 = y2 as usize;
 
-        let tmp = self.board[x1 as usize][y1 as usize];
-        self.board[x1][y1] = self.board[x2][y2];
+        let tmp = self.board[x1][y1].take();
+        self.board[x1][y1] = self.board[x2][y2].take();
         self.board[x2][y2] = tmp;
+        self.debug_assert_invariants();
+    }
+
+    /// Panics if the board is not square, has more than one empty position or contains a tile
+    /// more than once. Called after every `set` and `swap` (and so after every move), to catch
+    /// corrupted boards as early as possible. Does nothing in release builds.
+    pub fn debug_assert_invariants(&self) {
+        if !cfg!(debug_assertions) { return; }
+
+        let size = self.size();
+        assert!(self.board.iter().all(|column| column.len() == size), "Invalid Board: the board is not square.");
+        let tiles: Vec<&Option<T>> = self.board.iter().flatten().collect();
+        assert!(tiles.iter().filter(|tile| tile.is_none()).count() <= 1, "Invalid Board: there is more than one empty position.");
+        for (i, tile) in tiles.iter().enumerate() {
+            assert!(tile.is_none() || !tiles[..i].contains(tile), "Invalid Board: a tile appears more than once.");
+        }
+    }
+
+    /// Returns the (x, y) location of the empty position on the board, or None if there is no
+    /// empty position.
+    pub fn blank_position(&self) -> Option<(u8, u8)> {
+        for i in 0..self.size() {
+            for j in 0..self.size() {
+                if self.board[i][j].is_none() { return Some((i as u8, j as u8)) }
+            }
+        }
+        None
+    }
+
+    /// Returns the number of rows plus columns between the empty position and position x,y,
+    /// which is the least number of moves that bring the empty position there.
+    /// Panics if there is no empty position.
+    pub fn blank_distance_to(&self, x: u8, y: u8) -> u32 {
+        let (blank_x, blank_y) = self.blank_position().expect("Invalid Board: There are no empty positions on the board.");
+        (blank_x.abs_diff(x) + blank_y.abs_diff(y)) as u32
+    }
+
+    // returns the position of the tile that moves into the empty position if the move is
+    // performed, or None if the move is not possible
+    fn moving_tile_position(&self, m: Move) -> Option<(u8, u8)> {
+        self.tile_position_next_to(self.blank_position()?, m)
+    }
+
+    // like moving_tile_position, for callers that already know where the empty position is
+    fn tile_position_next_to(&self, (x, y): (u8, u8), m: Move) -> Option<(u8, u8)> {
+        let (row_delta, column_delta) = m.delta();
+        let x = x.checked_add_signed(column_delta)?;
+        let y = y.checked_add_signed(row_delta)?;
+        if (x as usize) < self.size() && (y as usize) < self.size() { Some((x, y)) } else { None }
+    }
+
+    /// Returns the tile that slides into the empty position if the move is performed, or None if
+    /// the move is not possible.
+    pub fn tile_for_move(&self, m: Move) -> Option<T> {
+        let (x, y) = self.moving_tile_position(m)?;
+        self.get(x, y)
+    }
+
+    /// Returns true if the move is possible in this state, i.e. if there is a tile on the side
+    /// of the empty position that the tile comes from. Doesn't change the state.
+    pub fn can_move(&self, m: Move) -> bool {
+        self.moving_tile_position(m).is_some()
+    }
+
+    /// Returns all moves that are possible in this state.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        Move::ALL.into_iter().filter(|m| self.can_move(*m)).collect()
     }
 
     /// Updates the state to reflect the move that was performed. Returns false if the move was
     /// not possible.
     pub fn perform_move(&mut self, m: Move) -> bool {
-        let (x, y) = self.empty_loc();
-        match m {
-            Move::LeftToRight => if  x == 0  { false } else { self.swap(x, y, x - 1, y); true}
-            Move::RightToLeft => if  x == 3  { false } else { self.swap(x, y, x + 1, y); true}
-            Move::BottomToTop => if  y == 3  { false } else { self.swap(x, y, x, y + 1); true}
-            Move::TopToBottom => if  y == 0  { false } else { self.swap(x, y, x, y - 1); true}
-        }
+        let (x, y) = self.blank_position().expect("Invalid Board: There are no empty positions on the board.");
+        let Some((tile_x, tile_y)) = self.tile_position_next_to((x, y), m) else { return false; };
+
+        self.swap(x, y, tile_x, tile_y);
+        true
     }
 
     /// Performs a series of moves. Returns the number of moves that were successful.
@@ -132,14 +345,197 @@ impl GameState {
             if self.perform_move(*i) {count += 1;}
         }
 
-        return count;
+        count
+    }
+
+    /// Performs a series of moves, skipping the ones that are not possible. Returns whether each
+    /// move was successful, in the order of the moves.
+    pub fn perform_moves_detailed(&mut self, moves: &[Move]) -> Vec<bool> {
+        moves.iter().map(|m| self.perform_move(*m)).collect()
+    }
+
+    /// Moves the empty position one step in the direction, by sliding the tile next to it the
+    /// other way. Returns false if the empty position is already at that edge of the board.
+    pub fn move_blank(&mut self, dir: Direction) -> bool {
+        self.perform_move(dir.to_move())
+    }
+
+    /// Returns every state that can be reached with a single move, together with that move.
+    pub fn neighbors(&self) -> Vec<(Move, GameState<T>)> {
+        Move::ALL.into_iter().filter_map(|m| Some((m, self.neighbor_for(m)?))).collect()
+    }
+
+    /// Returns the state that is reached with the move, or None if the move is not possible.
+    pub fn neighbor_for(&self, m: Move) -> Option<GameState<T>> {
+        let (x, y) = self.blank_position()?;
+        let (tile_x, tile_y) = self.tile_position_next_to((x, y), m)?;
+        let mut state = self.clone();
+        state.swap(x, y, tile_x, tile_y);
+        Some(state)
+    }
+
+    // builds a new state of the same size, where the tile at x,y is taken from the position
+    // that source(x, y) returns
+    fn transformed(&self, source: impl Fn(usize, usize) -> (usize, usize)) -> GameState<T> {
+        let size = self.size();
+        let mut board = vec![vec![None; size]; size];
+        for (x, column) in board.iter_mut().enumerate() {
+            for (y, tile) in column.iter_mut().enumerate() {
+                let (source_x, source_y) = source(x, y);
+                *tile = self.board[source_x][source_y].clone();
+            }
+        }
+        GameState{board}
+    }
+
+    /// Returns the state rotated or reflected by the symmetry of the square.
+    pub fn transform(&self, symmetry: Symmetry) -> GameState<T> {
+        let last = self.size() - 1;
+        match symmetry {
+            Symmetry::Identity => self.clone(),
+            Symmetry::RotateCw => self.transformed(|x, y| (y, last - x)),
+            Symmetry::Rotate180 => self.transformed(|x, y| (last - x, last - y)),
+            Symmetry::RotateCcw => self.transformed(|x, y| (last - y, x)),
+            Symmetry::MirrorHorizontal => self.transformed(|x, y| (last - x, y)),
+            Symmetry::MirrorDiagonal => self.transformed(|x, y| (y, x)),
+            Symmetry::MirrorVertical => self.transformed(|x, y| (x, last - y)),
+            Symmetry::MirrorAntiDiagonal => self.transformed(|x, y| (last - y, last - x)),
+        }
+    }
+
+    /// Returns every symmetry of the square that leaves the board unchanged, in the order of
+    /// `Symmetry::ALL`. Only boards with repeated tiles (which `validate` rejects) can have
+    /// any symmetry other than `Symmetry::Identity`.
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        Symmetry::ALL.into_iter().filter(|symmetry| self.transform(*symmetry) == *self).collect()
+    }
+}
+
+impl GameState {
+    /// Creates the solved position of a board with the given number of rows and columns,
+    /// starting with 1 in the top left corner and the empty position in the bottom right corner.
+    pub fn solved(size: usize) -> Self {
+        let mut board = vec![vec![None; size]; size];
+        for (x, column) in board.iter_mut().enumerate() {
+            for (y, tile) in column.iter_mut().enumerate() {
+                if x != size - 1 || y != size - 1 { *tile = Some((y * size + x + 1) as u8); }
+            }
+        }
+        Self{board}
+    }
+
+    /// Returns false if there is a duplicate tile in this game state.
+    pub fn all_tiles_unique(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Returns every tile that appears more than once, in increasing order. More than one empty
+    /// position is reported as tile 0.
+    pub fn duplicate_tiles(&self) -> Vec<u8> {
+        let mut counts: BTreeMap<u8, usize> = BTreeMap::new();
+        for tile in self.tiles() {
+            *counts.entry(tile.unwrap_or(0)).or_insert(0) += 1;
+        }
+        counts.into_iter().filter(|(_, count)| *count > 1).map(|(tile, _)| tile).collect()
+    }
+
+    /// Checks that the board is square, contains every tile at most once, only contains tiles
+    /// that fit the board size, and has exactly one empty position.
+    /// Returns which of these rules is broken otherwise.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let size = self.size();
+        if size < 2 || self.board.iter().any(|column| column.len() != size) {
+            return Err(ValidationError::WrongDimensions);
+        }
+
+        let tiles = self.tiles();
+        let max_tile = (size * size - 1) as u8;
+        if let Some(tile) = tiles.iter().flatten().find(|tile| !(1..=max_tile).contains(*tile)) {
+            return Err(ValidationError::TileOutOfRange(*tile));
+        }
+
+        match tiles.iter().filter(|tile| tile.is_none()).count() {
+            0 => return Err(ValidationError::NoBlank),
+            1 => {},
+            _ => return Err(ValidationError::MultipleBlanks),
+        }
+
+        // if we have seen tile i so far on the board, i is set to true
+        let mut seen = vec![false; size * size];
+        for tile in tiles.into_iter().flatten() {
+            if seen[tile as usize] { return Err(ValidationError::DuplicateTile(tile)); }
+            seen[tile as usize] = true;
+        }
+        Ok(())
+    }
+
+    /// Like `swap`, but returns an error (and leaves the state unchanged) instead of panicking if
+    /// either position is not on the board.
+    pub fn try_swap(&mut self, x1: u8, y1: u8, x2: u8, y2: u8) -> Result<(), OutOfBounds> {
+        let size = self.size();
+        for (x, y) in [(x1, y1), (x2, y2)] {
+            if x as usize >= size || y as usize >= size { return Err(OutOfBounds { x, y }); }
+        }
+        self.swap(x1, y1, x2, y2);
+        Ok(())
+    }
+
+    /// Swaps the positions of the two numbered tiles. Returns false (and leaves the state
+    /// unchanged) if either tile is not on the board.
+    /// Swapping two tiles always flips whether the state is solvable.
+    pub fn swap_tiles(&mut self, a: u8, b: u8) -> bool {
+        match (self.position_of(a), self.position_of(b)) {
+            (Some((x1, y1)), Some((x2, y2))) => { self.swap(x1, y1, x2, y2); true }
+            _ => false,
+        }
+    }
+
+    /// Same as `perform_move` in `MoveMode::Standard`. In `MoveMode::Torus` the board wraps
+    /// around, so a move at an edge slides the tile from the opposite edge into the empty position
+    /// instead of failing.
+    pub fn perform_move_mode(&mut self, m: Move, mode: MoveMode) -> bool {
+        if mode == MoveMode::Standard { return self.perform_move(m); }
+
+        let (x, y) = self.blank_position().expect("Invalid Board: There are no empty positions on the board.");
+        let last = (self.size() - 1) as u8;
+        match m {
+            Move::LeftToRight => self.swap(x, y, if x == 0 { last } else { x - 1 }, y),
+            Move::RightToLeft => self.swap(x, y, if x == last { 0 } else { x + 1 }, y),
+            Move::BottomToTop => self.swap(x, y, x, if y == last { 0 } else { y + 1 }),
+            Move::TopToBottom => self.swap(x, y, x, if y == 0 { last } else { y - 1 }),
+        }
+        true
+    }
+
+    /// Parses the moves with `parse_moves` and performs them. Returns the number of moves that
+    /// were successful; nothing is performed if the string can't be parsed.
+    pub fn apply_str(&mut self, moves: &str) -> Result<usize, ParseMoveError> {
+        Ok(self.perform_moves(&parse_moves(moves)?))
+    }
+
+    /// Performs a series of moves, stopping at the first move that is not possible.
+    /// Returns the index of that move together with the move; the state is left as it was right
+    /// before it.
+    pub fn perform_moves_strict(&mut self, moves: &[Move]) -> Result<(), (usize, Move)> {
+        for (i, m) in moves.iter().enumerate() {
+            if !self.perform_move(*m) { return Err((i, *m)); }
+        }
+        Ok(())
+    }
+
+    /// Lazily yields the state after each move, starting from this state. Like `perform_moves`,
+    /// moves that are not possible are skipped, so they don't yield a state.
+    pub fn replay<'a>(&self, moves: &'a [Move]) -> impl Iterator<Item = GameState> + 'a {
+        let mut state = self.clone();
+        moves.iter().filter_map(move |m| if state.perform_move(*m) { Some(state.clone()) } else { None })
     }
 
     /// Tries to parse a game state from the provided string.
     /// Returns None if parsing is not possible, or if the parsed game state would contain
     /// duplicate or invalid tiles.
-    /// Ignores whitespace.
-    pub fn from_str(s: &str) -> Option<Self> {
+    /// Ignores whitespace. Only parses 4x4 boards; see `from_str_strict` or `from_loose_str` for
+    /// other sizes.
+    pub fn parse_grid(s: &str) -> Option<Self> {
         let mut matrix = vec![vec![None; 4]; 4]; // Initialize a 4x4 matrix of None
 
         // Split the input into lines
@@ -172,321 +568,4088 @@ impl GameState {
         // println!("{:?}, Unique: {}", state.board, GameState::all_tiles_unique(&state));
         if GameState::all_tiles_unique(&state) {Some(state)} else {None}
     }
-}
 
-/// Finds the minimal number of moves needed to get from one state to the other.
-/// Might run forever if there is no path, so use with caution!
-pub fn find_shortest_path(from: GameState, to: GameState) -> Vec<Move> {
-    let mut paths: Vec::<Vec<Move>> = vec![];
-    paths.push(vec![]);
+    /// Creates a 4x4 board from rows of tiles, so `grid[y][x]` is the tile at position x,y.
+    /// Returns None if the board would contain duplicate or invalid tiles.
+    pub fn from_grid(grid: [[Option<u8>; 4]; 4]) -> Option<Self> {
+        let mut board = vec![vec![None; 4]; 4];
+        for (y, row) in grid.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                board[x][y] = *tile;
+            }
+        }
+        let state = GameState{board};
+        if state.validate().is_ok() {Some(state)} else {None}
+    }
 
-    // states are keys and values are the shortest paths to reach that state
-    let mut states_discovered: HashMap<Vec<Vec<Option<u8>>>, bool> = HashMap::new(); 
+    /// Parses several boards in the format of `parse_grid`, separated by one or more blank
+    /// lines. Returns one result per board, in order; a board that can't be parsed gives its
+    /// index (counting from 0) instead. Like `parse_grid`, only parses 4x4 boards.
+    pub fn parse_many(s: &str) -> Vec<Result<GameState, usize>> {
+        let mut blocks: Vec<String> = vec![];
+        let mut in_block = false;
+        for line in s.lines() {
+            if line.trim().is_empty() {
+                in_block = false;
+            } else {
+                if !in_block { blocks.push(String::new()); }
+                in_block = true;
+                let block = blocks.last_mut().unwrap();
+                block.push_str(line);
+                block.push('\n');
+            }
+        }
 
-    for _i in 1..=1000 { 
-        let mut new_paths: Vec::<Vec<Move>> = vec![];
-        for path in paths {
-            for mv in vec![Move::LeftToRight, Move::RightToLeft, Move::TopToBottom, Move::BottomToTop] {
-                let mut curr_state = from.clone();
-                curr_state.perform_moves(&path);
-                let mut new_path = path.clone(); 
-
-                if curr_state.perform_move(mv) {     
-                    if states_discovered.contains_key(&curr_state.board) {
-                        // ignore this move since we've already reached it once
-                    }
-                    else {
-                        // add this state to our hashmap
-                        states_discovered.insert(curr_state.board.clone(), true);
+        blocks.iter()
+            .enumerate()
+            .map(|(index, block)| GameState::parse_grid(block).ok_or(index))
+            .collect()
+    }
 
-                        // add this path to our new paths
-                        new_path.push(mv); 
-                        new_paths.push(new_path.clone()); 
-                    }
+    /// Parses a board of any size in exactly the format produced by `Display`: every cell must
+    /// be padded to the width of the largest tile, and every row must end with a newline.
+    /// Returns None for anything else, so `from_str_strict(&state.to_string())` always gives
+    /// back the state.
+    pub fn from_str_strict(s: &str) -> Option<Self> {
+        let rows: Vec<&str> = s.lines().collect();
+        let size = rows.len();
+        let mut board = vec![vec![None; size]; size];
+        for (y, row) in rows.iter().enumerate() {
+            let cells: Vec<&str> = row.split('|').collect();
+            // a row starts and ends with '|', so splitting gives an empty string at both ends
+            if cells.len() != size + 2 || !cells[0].is_empty() || !cells[size + 1].is_empty() { return None; }
+            for x in 0..size {
+                let cell = cells[x + 1].trim();
+                if !cell.is_empty() { board[x][y] = Some(cell.parse::<u8>().ok()?); }
+            }
+        }
 
-                    
-                }
+        let state = GameState{board};
+        // the cells were parsed leniently, so compare against the canonical output
+        if state.validate().is_ok() && state.to_string() == s {Some(state)} else {None}
+    }
 
-                if curr_state == to { return new_path; }
-            };
-        };
+    /// Parses a board of any size from text pasted from elsewhere, as permissively as possible.
+    /// Cells may be separated by `|`, `+`, `-` or whitespace, lines made only of separators
+    /// (such as `+--+--+`) are skipped, and any cell that is not a number (or is `0`) is the
+    /// empty position. Empty cells are only recognized between `|` or `+`.
+    /// Returns None if the result is not square or not a valid state, see `validate`.
+    pub fn from_loose_str(s: &str) -> Option<Self> {
+        let is_separator = |c: char| c == '|' || c == '+' || c == '-' || c.is_whitespace();
+        let rows: Vec<Vec<Option<u8>>> = s
+            .lines()
+            .filter(|line| !line.chars().all(is_separator))
+            .map(|line| {
+                let line = line.trim();
+                let cells: Vec<&str> = if line.contains(['|', '+']) {
+                    // a border at the start or end of the row doesn't start another cell
+                    let line = line.strip_prefix(['|', '+']).unwrap_or(line);
+                    let line = line.strip_suffix(['|', '+']).unwrap_or(line);
+                    line.split(['|', '+']).map(|cell| cell.trim_matches(is_separator)).collect()
+                } else {
+                    line.split(is_separator).filter(|cell| !cell.is_empty()).collect()
+                };
+                cells.iter().map(|cell| cell.parse::<u8>().ok().filter(|tile| *tile != 0)).collect()
+            })
+            .collect();
 
-        paths = new_paths;
-        // println!("Paths for i = {}\n{:?}", i, paths);
-    };  
+        let state = GameState::from_rows(rows)?;
+        if state.validate().is_ok() {Some(state)} else {None}
+    }
 
-    panic!("Did not find any valid path of any valid length");
-}
+    /// Parses a board of any size where `blank_token` marks the empty position, e.g. "0", "x" or
+    /// "16" as used by some datasets. Rows are either in the format produced by `Display`, or
+    /// tiles separated by whitespace if a row contains no `|`. An empty cell between `|` is only
+    /// allowed if `blank_token` is empty.
+    /// Returns None if parsing is not possible, or if the result is not a valid state.
+    pub fn from_str_with_blank(s: &str, blank_token: &str) -> Option<Self> {
+        let mut rows: Vec<Vec<Option<u8>>> = vec![];
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let cells: Vec<&str> = if line.contains('|') {
+                let inner = line.strip_prefix('|')?.strip_suffix('|')?;
+                inner.split('|').map(str::trim).collect()
+            } else {
+                line.split_whitespace().collect()
+            };
+            let row = cells.iter()
+                .map(|cell| if *cell == blank_token { Some(None) } else { cell.parse::<u8>().ok().map(Some) })
+                .collect::<Option<Vec<Option<u8>>>>()?;
+            rows.push(row);
+        }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
-#[repr(u8)]
-pub enum Move {
-    LeftToRight,
-    RightToLeft,
-    TopToBottom,
-    BottomToTop,
-}
+        let state = GameState::from_rows(rows)?;
+        if state.validate().is_ok() {Some(state)} else {None}
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Generates the representation of `Display`, with the changes described by the config.
+    /// The columns are widened if the blank symbol is longer than the largest tile.
+    pub fn to_string_with(&self, config: &DisplayConfig) -> String {
+        let width = self.tile_width().max(config.blank.chars().count());
+        let mut str = String::new();
+        for y in 0..self.size() {
+            for x in 0..self.size() {
+                match self.board[x][y] {
+                    Some(tile) => str.push_str(&format!("| {:>width$} ", tile)),
+                    None => str.push_str(&format!("| {:>width$} ", config.blank)),
+                }
+            }
+            str.push_str("|\n");
+        }
+        str
+    }
 
-    #[test]
-    fn test_default_game_state() {
-        let state = GameState::default();
-        assert_eq!(state.get(0, 0), Some(1));
-        assert_eq!(state.get(1, 0), Some(2));
-        assert_eq!(state.get(2, 0), Some(3));
-        assert_eq!(state.get(3, 0), Some(4));
-        assert_eq!(state.get(0, 1), Some(5));
-        assert_eq!(state.get(1, 1), Some(6));
-        assert_eq!(state.get(2, 1), Some(7));
-        assert_eq!(state.get(3, 1), Some(8));
-        assert_eq!(state.get(0, 2), Some(9));
-        assert_eq!(state.get(1, 2), Some(10));
-        assert_eq!(state.get(2, 2), Some(11));
-        assert_eq!(state.get(3, 2), Some(12));
-        assert_eq!(state.get(0, 3), Some(13));
-        assert_eq!(state.get(1, 3), Some(14));
-        assert_eq!(state.get(2, 3), Some(15));
-        assert_eq!(state.get(3, 3), None);
+    // returns the number of digits of the largest tile
+    fn tile_width(&self) -> usize {
+        (self.size() * self.size() - 1).to_string().len()
     }
 
-    #[test]
-    fn test_set_game_state() {
-        let mut state = GameState::default();
-        state.set(0, 2, Some(3));
-        assert_eq!(state.get(0, 2), Some(3));
-        // TODO: add more tests
+    /// Generates a minimal representation of the game state: tiles separated by single spaces,
+    /// `.` for the empty position. Cells are left-aligned to the width of the largest tile.
+    pub fn to_compact_string(&self) -> String {
+        let size = self.size();
+        let width = self.tile_width();
+        let mut str = String::new();
+        for y in 0..size {
+            let cells: Vec<String> = (0..size)
+                .map(|x| match self.board[x][y] {
+                    Some(tile) => format!("{:<width$}", tile),
+                    None => format!("{:<width$}", "."),
+                })
+                .collect();
+            str.push_str(cells.join(" ").trim_end());
+            str.push('\n');
+        }
+        str
     }
 
-    const DEFAULT_STATE_STR: &'static str = "\
-|  1 |  2 |  3 |  4 |
-|  5 |  6 |  7 |  8 |
-|  9 | 10 | 11 | 12 |
-| 13 | 14 | 15 |    |
-";
+    /// Tries to parse a game state from the compact format produced by `to_compact_string`.
+    /// Tiles are separated by any amount of whitespace, `.` or `_` marks the empty position.
+    /// The board has as many rows as there are non-empty lines, so every line must have that
+    /// many tiles too.
+    /// Returns None if parsing is not possible, or if the parsed game state would contain
+    /// duplicate or invalid tiles.
+    pub fn from_compact_str(s: &str) -> Option<Self> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
 
-    #[test]
-    fn test_display_game_state() {
-        let state = GameState::default();
-        assert_eq!(DEFAULT_STATE_STR, format!("{state}"));
+        let size = rows.len();
+        let mut matrix = vec![vec![None; size]; size];
+        for (row_index, row) in rows.iter().enumerate() {
+            let elements: Vec<&str> = row.split_whitespace().collect();
+            if elements.len() != size { return None }; // invalid # of cols
+            for (col_index, element) in elements.iter().enumerate() {
+                match *element {
+                    "." | "_" => matrix[col_index][row_index] = None,
+                    _ => match element.parse::<u8>() {
+                        Ok(value) => matrix[col_index][row_index] = Some(value),
+                        Err(_) => return None,
+                    },
+                }
+            }
+        }
 
-        // TODO: add more tests
+        let state = GameState{board: matrix};
+        if state.all_tiles_unique() {Some(state)} else {None}
     }
 
-    #[test]
-    fn test_validate_game_state() {
-        let mut state = GameState::default();
-        assert!(state.all_tiles_unique());
-        state.set(3, 0, Some(1));
-        assert!(!state.all_tiles_unique());
-        state.set(0, 0, Some(4));
+    /// Iterates over all positions row by row, yielding (row, column, tile). Note that the row
+    /// is the y coordinate and the column the x coordinate of `get`.
+    pub fn cells(&self) -> impl Iterator<Item = (u8, u8, Option<u8>)> + '_ {
+        let size = self.size();
+        (0..size).flat_map(move |y| (0..size).map(move |x| (y as u8, x as u8, self.board[x][y])))
+    }
+
+    // returns all tiles in row-major order
+    fn tiles(&self) -> Vec<Option<u8>> {
+        self.cells().map(|(_, _, tile)| tile).collect()
+    }
+
+    // returns all tiles in row-major order, with 0 for the empty position
+    fn flat(&self) -> Vec<u8> {
+        self.cells().map(|(_, _, tile)| tile.unwrap_or(0)).collect()
+    }
+
+    // inverse of flat; returns None if the tiles don't form a valid board of the given size
+    fn from_flat(size: usize, tiles: &[u8]) -> Option<Self> {
+        if size.checked_mul(size) != Some(tiles.len()) { return None; }
+
+        let mut board = vec![vec![None; size]; size];
+        for (i, tile) in tiles.iter().enumerate() {
+            board[i % size][i / size] = if *tile == 0 { None } else { Some(*tile) };
+        }
+        let state = GameState{board};
+        if state.validate().is_ok() {Some(state)} else {None}
+    }
+
+    /// Returns the number of inversions: pairs of numbered tiles where the larger tile comes
+    /// first when reading the board row by row, skipping the empty position.
+    /// Horizontal moves never change it; on boards with an odd width, vertical moves change it
+    /// by an even number, which is why half of all boards can't be solved.
+    pub fn inversion_count(&self) -> usize {
+        let tiles: Vec<u8> = self.tiles().into_iter().flatten().collect();
+        let mut inversions = 0;
+        for i in 0..tiles.len() {
+            for j in i + 1..tiles.len() {
+                if tiles[i] > tiles[j] { inversions += 1; }
+            }
+        }
+        inversions
+    }
+
+    // returns a value that no move can change: the parity of the number of inversions, plus the
+    // row of the empty position (counted from the bottom) on boards with an even width, where
+    // vertical moves flip the inversion parity. It is 0 for the solved board.
+    fn solvability_parity(&self) -> usize {
+        let inversions = self.inversion_count();
+        let size = self.size();
+        let blank_row = size - 1 - self.blank_position().unwrap().1 as usize;
+        if size.is_multiple_of(2) { (inversions + blank_row) % 2 } else { inversions % 2 }
+    }
+
+    /// Returns whether the number of inversions (plus the row of the empty position, counted
+    /// from the bottom, on boards with an even width) is even or odd. No move changes it, so
+    /// two boards with the same size and tiles can reach each other exactly if their parity is
+    /// the same. The solved board is `Parity::Even`.
+    pub fn parity(&self) -> Parity {
+        if self.solvability_parity() == 0 { Parity::Even } else { Parity::Odd }
+    }
+
+    // returns true if the goal can be reached from this state by some sequence of moves
+    fn can_reach(&self, goal: &GameState) -> bool {
+        if self.size() != goal.size() { return false; }
+
+        let mut tiles = self.tiles();
+        let mut goal_tiles = goal.tiles();
+        tiles.sort();
+        goal_tiles.sort();
+        tiles == goal_tiles && self.solvability_parity() == goal.solvability_parity()
+    }
+
+    /// Returns the sum of the distances (in rows plus columns) between every numbered tile's
+    /// position and its position in the goal state.
+    pub fn manhattan_distance(&self, goal: &GameState) -> u32 {
+        let (rows, columns) = self.manhattan_components(goal);
+        rows + columns
+    }
+
+    /// Returns the two parts of `manhattan_distance` separately: the sum of the distances in
+    /// rows, and the sum of the distances in columns.
+    pub fn manhattan_components(&self, goal: &GameState) -> (u32, u32) {
+        let size = self.size();
+        let mut goal_positions = vec![(0, 0); size * size];
+        for x in 0..size {
+            for y in 0..size {
+                if let Some(tile) = goal.board[x][y] { goal_positions[tile as usize] = (x, y); }
+            }
+        }
+
+        let (mut rows, mut columns) = (0, 0);
+        for x in 0..size {
+            for y in 0..size {
+                if let Some(tile) = self.board[x][y] {
+                    let (goal_x, goal_y) = goal_positions[tile as usize];
+                    rows += y.abs_diff(goal_y) as u32;
+                    columns += x.abs_diff(goal_x) as u32;
+                }
+            }
+        }
+        (rows, columns)
+    }
+
+    /// Returns the distance (in rows plus columns) between the tile's position and its position
+    /// in the goal state, or None if the tile is not part of both states.
+    pub fn tile_distance(&self, tile: u8, goal: &GameState) -> Option<u32> {
+        let (x, y) = self.position_of(tile)?;
+        let (goal_x, goal_y) = goal.position_of(tile)?;
+        Some((x.abs_diff(goal_x) + y.abs_diff(goal_y)) as u32)
+    }
+
+    /// Returns the distance of every tile to its position in the goal state, by position on
+    /// this board: `matrix[y][x]` is the distance of the tile at position x,y. The empty
+    /// position gets 0. The entries add up to `manhattan_distance`.
+    /// Panics if the board is not 4x4.
+    pub fn distance_matrix(&self, goal: &GameState) -> [[u32; 4]; 4] {
+        assert!(self.size() == 4, "the distance matrix is only defined for 4x4 boards");
+        let mut matrix = [[0; 4]; 4];
+        for (y, x, tile) in self.cells() {
+            if let Some(tile) = tile {
+                matrix[y as usize][x as usize] = self.tile_distance(tile, goal).unwrap_or(0);
+            }
+        }
+        matrix
+    }
+
+    /// Returns the average Manhattan distance of a numbered tile to its goal position, as a cheap
+    /// measure of how scrambled the state is. 0 only for the goal state itself.
+    pub fn disorder_score(&self, goal: &GameState) -> f64 {
+        let tiles = self.size() * self.size() - 1;
+        self.manhattan_distance(goal) as f64 / tiles as f64
+    }
+
+    /// Returns the number of numbered tiles that are not at their position in the goal state.
+    pub fn misplaced_tiles(&self, goal: &GameState) -> u32 {
+        let size = self.size();
+        let mut misplaced = 0;
+        for x in 0..size {
+            for y in 0..size {
+                if self.board[x][y].is_some() && self.board[x][y] != goal.board[x][y] { misplaced += 1; }
+            }
+        }
+        misplaced
+    }
+
+    /// Returns the number of numbered tiles that are already at their position in the goal state.
+    pub fn tiles_in_place(&self, goal: &GameState) -> u8 {
+        self.cells()
+            .filter(|(y, x, tile)| tile.is_some() && *tile == goal.get(*x, *y))
+            .count() as u8
+    }
+
+    /// Returns the number of moves an optimal solution from this state to the goal needs, or
+    /// None if the goal cannot be reached.
+    pub fn optimal_distance(&self, goal: &GameState) -> Option<usize> {
+        find_shortest_path_idastar(self.clone(), goal.clone()).map(|path| path.len())
+    }
+
+    /// Returns true if the solved position of the same size (see `solved`) can be reached from
+    /// this state.
+    pub fn is_solvable(&self) -> bool {
+        self.can_reach(&GameState::solved(self.size()))
+    }
+
+    /// Generates a uniformly random state out of all states from which the default position can
+    /// be reached. Always generates 4x4 boards.
+    pub fn random_solvable(rng: &mut impl RandomSource) -> Self {
+        let mut tiles: Vec<Option<u8>> = GameState::default().tiles();
+        // Fisher-Yates shuffle
+        for i in (1..tiles.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            tiles.swap(i, j);
+        }
+
+        let mut board = vec![vec![None; 4]; 4];
+        for (i, tile) in tiles.into_iter().enumerate() {
+            board[i % 4][i / 4] = tile;
+        }
+        let mut state = GameState{board};
+
+        // exactly half of all permutations are solvable; swapping two numbered tiles switches
+        // between the two halves
+        if !state.is_solvable() {
+            let numbered: Vec<usize> = state.tiles().iter().enumerate()
+                .filter(|(_, tile)| tile.is_some())
+                .map(|(i, _)| i)
+                .take(2)
+                .collect();
+            state.swap((numbered[0] % 4) as u8, (numbered[0] / 4) as u8, (numbered[1] % 4) as u8, (numbered[1] / 4) as u8);
+        }
+        state
+    }
+
+    /// Checks whether the state matches a partial goal. The pattern is given row by row, so
+    /// `pattern[y][x]` is the position x,y. `None` matches any tile (or the empty position),
+    /// `Some(n)` requires tile n at that position. The pattern can be any square grid of the
+    /// board's size, e.g. `[[Option<u8>; 3]; 3]` for a 3x3 board; it never matches a board of
+    /// another size.
+    pub fn matches_pattern(&self, pattern: &[impl AsRef<[Option<u8>]>]) -> bool {
+        let size = self.size();
+        if pattern.len() != size || pattern.iter().any(|row| row.as_ref().len() != size) { return false; }
+
+        for (y, row) in pattern.iter().enumerate() {
+            for (x, cell) in row.as_ref().iter().enumerate() {
+                if cell.is_some() && self.board[x][y] != *cell { return false; }
+            }
+        }
+        true
+    }
+
+    /// Checks whether the moves solve this state, i.e. lead to the solved position of the same
+    /// size (see `solved`). Returns false if any of the moves is not possible, even if the solved
+    /// position would be reached by skipping it.
+    pub fn verify_solution(&self, moves: &[Move]) -> bool {
+        let mut state = self.clone();
+        state.perform_moves(moves) == moves.len() && state == GameState::solved(self.size())
+    }
+
+    /// Performs the moves on a copy of this state, then undoes every successful one in reverse
+    /// order, and checks that this leads back to this state. Moves that are not possible are
+    /// skipped, so they are not undone either. Returns false if undoing a move fails.
+    pub fn round_trip(&self, moves: &[Move]) -> bool {
+        let mut state = self.clone();
+        let performed: Vec<Move> = moves.iter()
+            .zip(state.perform_moves_detailed(moves))
+            .filter(|(_, success)| *success)
+            .map(|(m, _)| *m)
+            .collect();
+        let undone = performed.iter().rev().all(|m| state.perform_move(m.inverse()));
+        undone && state == *self
+    }
+
+    /// Slides the tile into the empty position, as if it was clicked. Returns false (and leaves
+    /// the state unchanged) if the tile is not next to the empty position.
+    pub fn slide_tile(&mut self, tile: u8) -> bool {
+        match Move::ALL.into_iter().find(|m| self.tile_for_move(*m) == Some(tile)) {
+            Some(m) => self.perform_move(m),
+            None => false,
+        }
+    }
+
+    /// Returns the tiles next to the empty position, which are the ones that can slide, in
+    /// increasing order.
+    pub fn movable_tiles(&self) -> Vec<u8> {
+        let mut tiles: Vec<u8> = Move::ALL.into_iter().filter_map(|m| self.tile_for_move(m)).collect();
+        tiles.sort();
+        tiles
+    }
+
+    /// Pushes the tile and every tile between it and the empty position one step towards the
+    /// empty position, if the tile is in the same row or column. Returns how many tiles moved,
+    /// which is 0 if the tile is not in line with the empty position.
+    pub fn slide_row_to_blank(&mut self, tile: u8) -> usize {
+        let ((x, y), (blank_x, blank_y)) = match (self.position_of(tile), self.blank_position()) {
+            (Some(tile), Some(blank)) => (tile, blank),
+            _ => return 0,
+        };
+        let (m, count) = if y == blank_y && x < blank_x {
+            (Move::LeftToRight, blank_x - x)
+        } else if y == blank_y && x > blank_x {
+            (Move::RightToLeft, x - blank_x)
+        } else if x == blank_x && y < blank_y {
+            (Move::TopToBottom, blank_y - y)
+        } else if x == blank_x && y > blank_y {
+            (Move::BottomToTop, y - blank_y)
+        } else {
+            return 0;
+        };
+
+        for _ in 0..count {
+            self.perform_move(m);
+        }
+        count as usize
+    }
+
+    /// Returns the move that turns this state into the other state, or None if the states are
+    /// equal or more than one move apart.
+    pub fn is_adjacent(&self, other: &GameState) -> Option<Move> {
+        self.neighbors().into_iter().find(|(_, state)| state == other).map(|(m, _)| m)
+    }
+
+    /// Returns the state rotated a quarter turn clockwise.
+    /// The rotated state is not necessarily solvable, even if this one is.
+    pub fn rotate_cw(&self) -> GameState {
+        let last = self.size() - 1;
+        self.transformed(|x, y| (y, last - x))
+    }
+
+    /// Returns the state rotated a quarter turn counterclockwise.
+    /// The rotated state is not necessarily solvable, even if this one is.
+    pub fn rotate_ccw(&self) -> GameState {
+        let last = self.size() - 1;
+        self.transformed(|x, y| (last - y, x))
+    }
+
+    /// Returns the state mirrored left to right, so the first column becomes the last one.
+    pub fn mirror_horizontal(&self) -> GameState {
+        let last = self.size() - 1;
+        self.transformed(|x, y| (last - x, y))
+    }
+
+    /// Returns the state mirrored top to bottom, so the first row becomes the last one.
+    pub fn mirror_vertical(&self) -> GameState {
+        let last = self.size() - 1;
+        self.transformed(|x, y| (x, last - y))
+    }
+
+    /// Returns the state with rows and columns swapped, so the first row becomes the first column.
+    /// The transposed state is not necessarily solvable, even if this one is.
+    pub fn transpose(&self) -> GameState {
+        self.transformed(|x, y| (y, x))
+    }
+
+    // returns the state under all 8 rotations and reflections of the square, starting with the
+    // state itself
+    fn dihedral_transforms(&self) -> Vec<GameState> {
+        Symmetry::ALL.into_iter().map(|symmetry| self.transform(symmetry)).collect()
+    }
+
+    /// Returns a single representative for all states that are rotations or reflections of each
+    /// other: the one whose tiles, read row by row with 0 for the empty position, are
+    /// lexicographically smallest.
+    pub fn canonical(&self) -> GameState {
+        self.dihedral_transforms().into_iter().min_by_key(|state| state.flat()).unwrap()
+    }
+
+    /// Buckets the state by the number of moves an optimal solution to the goal needs, see
+    /// `EASY_MAX_DISTANCE` and `MEDIUM_MAX_DISTANCE`.
+    pub fn difficulty(&self, goal: &GameState) -> Difficulty {
+        match self.optimal_distance(goal) {
+            None => Difficulty::Unsolvable,
+            Some(distance) if distance <= EASY_MAX_DISTANCE => Difficulty::Easy,
+            Some(distance) if distance <= MEDIUM_MAX_DISTANCE => Difficulty::Medium,
+            Some(_) => Difficulty::Hard,
+        }
+    }
+
+    /// Generates a JSON representation of the game state, e.g. `{"size":4,"tiles":[1,2,...,0]}`,
+    /// with the tiles in row-major order and 0 for the empty position.
+    pub fn to_json(&self) -> String {
+        let tiles: Vec<String> = self.flat().iter().map(|tile| tile.to_string()).collect();
+        format!("{{\"size\":{},\"tiles\":[{}]}}", self.size(), tiles.join(","))
+    }
+
+    /// Tries to parse a game state from the JSON representation produced by `to_json`.
+    /// Returns None if the JSON is malformed, has missing or unknown keys, or describes an
+    /// invalid game state.
+    pub fn from_json(s: &str) -> Option<Self> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut rest = s.strip_prefix('{')?.strip_suffix('}')?;
+
+        let mut size: Option<usize> = None;
+        let mut tiles: Option<Vec<u8>> = None;
+        while !rest.is_empty() {
+            let (key, value_and_rest) = rest.split_once(':')?;
+            let value_end = if value_and_rest.starts_with('[') {
+                value_and_rest.find(']')? + 1
+            } else {
+                value_and_rest.find(',').unwrap_or(value_and_rest.len())
+            };
+            let (value, after_value) = value_and_rest.split_at(value_end);
+
+            match key {
+                "\"size\"" if size.is_none() => size = Some(value.parse().ok()?),
+                "\"tiles\"" if tiles.is_none() => {
+                    let list = value.strip_prefix('[')?.strip_suffix(']')?;
+                    let parsed: Result<Vec<u8>, _> = list.split(',').map(str::parse).collect();
+                    tiles = Some(parsed.ok()?);
+                },
+                _ => return None, // unknown or repeated key
+            }
+
+            rest = match after_value.strip_prefix(',') {
+                Some("") => return None, // trailing comma
+                Some(after_comma) => after_comma,
+                None if after_value.is_empty() => after_value,
+                None => return None,
+            };
+        }
+
+        GameState::from_flat(size?, &tiles?)
+    }
+
+    /// Packs the state into a single number, 4 bits per position (0 for the empty position), with
+    /// the positions in row-major order starting at the lowest bits. Much cheaper to hash and
+    /// compare than the state itself. The solvers remember the states they discovered this way,
+    /// and fall back to all tiles for larger boards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board is larger than 4x4.
+    pub fn pack(&self) -> u64 {
+        assert!(self.size() <= 4, "only boards up to 4x4 can be packed");
+        self.flat().iter().rev().fold(0, |bits, tile| (bits << 4) | *tile as u64)
+    }
+
+    // the key the solvers remember discovered states by: the packed state where possible, all
+    // tiles for boards that are too large to be packed
+    fn search_key(&self) -> SearchKey {
+        if self.size() <= 4 { SearchKey::Packed(self.pack()) } else { SearchKey::Tiles(self.flat()) }
+    }
+
+    /// Inverse of `pack` for 4x4 boards. Returns None if the bits don't describe a valid state.
+    pub fn unpack(bits: u64) -> Option<Self> {
+        let tiles: Vec<u8> = (0..16).map(|i| ((bits >> (4 * i)) & 0xF) as u8).collect();
+        GameState::from_flat(4, &tiles)
+    }
+
+    /// Encodes the packed state (see `pack`) as 11 characters of URL-safe base64, without
+    /// padding, for sharing a board in a link. Panics if the board is larger than 4x4.
+    pub fn to_token(&self) -> String {
+        let bytes = self.pack().to_le_bytes();
+        let mut token = String::new();
+        for chunk in bytes.chunks(3) {
+            let mut group = [0; 3];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let bits = (group[0] as u32) << 16 | (group[1] as u32) << 8 | group[2] as u32;
+            // a chunk of n bytes needs n + 1 characters
+            for i in 0..=chunk.len() {
+                token.push(BASE64_URL[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            }
+        }
+        token
+    }
+
+    /// Inverse of `to_token` for 4x4 boards. Returns None if the token was not produced by
+    /// `to_token` or doesn't describe a valid state.
+    pub fn from_token(s: &str) -> Option<Self> {
+        if s.len() != 11 { return None; }
+
+        let mut bytes = vec![];
+        for chunk in s.as_bytes().chunks(4) {
+            let mut bits = 0;
+            for (i, c) in chunk.iter().enumerate() {
+                let value = BASE64_URL.iter().position(|b| b == c)? as u32;
+                bits |= value << (18 - 6 * i);
+            }
+            bytes.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+        }
+
+        // the last character has two bits to spare, which to_token leaves at 0
+        let bits = u64::from_le_bytes(bytes.try_into().ok()?);
+        let state = GameState::unpack(bits)?;
+        if state.to_token() != s { return None; }
+        Some(state)
+    }
+
+    /// Returns a hash that stays the same across runs, platforms and compiler versions, so it can
+    /// be stored: the 64-bit FNV-1a hash of the 8 bytes of `pack`, lowest byte first.
+    /// Panics if the board is larger than 4x4.
+    pub fn stable_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+        self.pack().to_le_bytes().iter().fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+    }
+
+    // returns the (x, y) location of the tile, or None if it is not on the board
+    fn position_of(&self, tile: u8) -> Option<(u8, u8)> {
+        self.cells().find(|(_, _, t)| *t == Some(tile)).map(|(y, x, _)| (x, y))
+    }
+
+    /// Returns the shortest sequence of moves that puts the tile at its position in the goal
+    /// state. Other tiles may end up anywhere. Returns an empty list if the tile is already in
+    /// place or not part of both states.
+    pub fn solve_tile(&self, tile: u8, goal: &GameState) -> Vec<Move> {
+        let (target, start) = match (goal.position_of(tile), self.position_of(tile)) {
+            (Some(target), Some(start)) => (target, start),
+            _ => return vec![],
+        };
+
+        // only the positions of the tile and the empty position matter
+        let mut discovered = Set::from([(start, self.blank_position())]);
+        let mut queue = VecDeque::from([(self.clone(), vec![])]);
+        while let Some((state, path)) = queue.pop_front() {
+            if state.position_of(tile) == Some(target) { return path; }
+
+            for (mv, next_state) in state.neighbors() {
+                if !discovered.insert((next_state.position_of(tile).unwrap(), next_state.blank_position())) { continue; }
+                let mut new_path = path.clone();
+                new_path.push(mv);
+                queue.push_back((next_state, new_path));
+            }
+        }
+        vec![]
+    }
+
+    /// Performs the given number of random moves and returns them. The moves are drawn from an
+    /// `XorShift64` generator with the given seed, so the same seed and state always lead to the
+    /// same moves. A move never undoes the one before it.
+    pub fn scramble_seeded(&mut self, moves: usize, seed: u64) -> Vec<Move> {
+        let mut rng = XorShift64::new(seed);
+        let mut performed: Vec<Move> = vec![];
+        for _ in 0..moves {
+            let candidates: Vec<Move> = self.legal_moves().into_iter()
+                .filter(|m| performed.last() != Some(&m.inverse()))
+                .collect();
+            let m = candidates[(rng.next_u64() % candidates.len() as u64) as usize];
+            self.perform_move(m);
+            performed.push(m);
+        }
+        performed
+    }
+
+    /// Returns the 4x4 puzzle for the given date: the default board scrambled with 40 random moves,
+    /// seeded from the date, so the same date always gives the same board. The date is not
+    /// checked for validity.
+    pub fn from_date(year: u32, month: u32, day: u32) -> GameState {
+        let date = year as u64 * 10_000 + month as u64 * 100 + day as u64;
+        // spreads the bits of nearby dates, so that consecutive days don't start alike
+        let seed = date.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let mut state = GameState::default();
+        state.scramble_seeded(40, seed);
+        state
+    }
+
+    // draws the grid with Unicode box-drawing characters, for the alternate Display form
+    fn to_box_string(&self) -> String {
+        let size = self.size();
+        let width = self.tile_width();
+        let line = "─".repeat(width + 2);
+        let border = |left: &str, middle: &str, right: &str| {
+            format!("{}{}{}\n", left, vec![line.as_str(); size].join(middle), right)
+        };
+
+        let mut str = border("┌", "┬", "┐");
+        for y in 0..size {
+            if y > 0 { str.push_str(&border("├", "┼", "┤")); }
+            for x in 0..size {
+                match self.board[x][y] {
+                    Some(tile) => str.push_str(&format!("│ {:>width$} ", tile)),
+                    None => str.push_str(&format!("│ {:width$} ", "")),
+                }
+            }
+            str.push_str("│\n");
+        }
+        str.push_str(&border("└", "┴", "┘"));
+        str
+    }
+
+    /// Returns the tiles row by row, with 0 for the empty position. Works for any board size.
+    pub fn as_permutation(&self) -> Vec<u8> {
+        self.flat()
+    }
+
+    /// Inverse of `as_permutation`; the board size is derived from the length. Returns None if the
+    /// length is not a square number or the tiles don't form a valid state.
+    pub fn from_permutation(p: &[u8]) -> Option<Self> {
+        let size = p.len().isqrt();
+        GameState::from_flat(size, p)
+    }
+
+    /// Returns the inverse of the permutation described by `as_permutation`: the tile at
+    /// position i of the result is the position of tile i in this state, with the empty
+    /// position counting as tile 0. Applying it twice gives back the original state.
+    pub fn inverse_state(&self) -> GameState {
+        let tiles = self.flat();
+        let mut inverse = vec![0; tiles.len()];
+        for (position, tile) in tiles.iter().enumerate() {
+            inverse[*tile as usize] = position as u8;
+        }
+        GameState::from_flat(self.size(), &inverse).expect("the inverse of a valid board is a valid board")
+    }
+
+    /// Draws the board as a self-contained SVG image, with every tile a square of `tile_px`
+    /// pixels showing its number. The empty position is left blank.
+    pub fn to_svg(&self, tile_px: u32) -> String {
+        let side = self.size() as u32 * tile_px;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{side}\" height=\"{side}\" viewBox=\"0 0 {side} {side}\">\n"
+        );
+        svg.push_str(&format!("<rect width=\"{side}\" height=\"{side}\" fill=\"#444\"/>\n"));
+        for (y, x, tile) in self.cells() {
+            let tile = match tile {
+                Some(tile) => tile,
+                None => continue,
+            };
+            let (left, top) = (x as u32 * tile_px, y as u32 * tile_px);
+            let (center_x, center_y) = (left + tile_px / 2, top + tile_px / 2);
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"#eee\" stroke=\"#444\"/>\n",
+                left + 1, top + 1, tile_px.saturating_sub(2), tile_px.saturating_sub(2), tile_px / 10
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{center_x}\" y=\"{center_y}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{tile}</text>\n",
+                tile_px * 2 / 5
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Returns the move that leads to the lowest Manhattan distance to the goal state, for
+    /// giving hints. On ties the move that comes first in `Move::ALL` wins. Every move changes the
+    /// distance by one, so if no move brings a tile closer the hint makes the distance larger.
+    /// Returns None if the state already is the goal state.
+    pub fn best_hint(&self, goal: &GameState) -> Option<Move> {
+        if self == goal { return None; }
+        self.neighbors()
+            .into_iter()
+            .min_by_key(|(_, state)| state.manhattan_distance(goal))
+            .map(|(m, _)| m)
+    }
+
+    /// Returns the possible moves that lead straight to the goal state. There is at most one
+    /// unless the goal contains a tile more than once.
+    pub fn winning_moves(&self, goal: &GameState) -> Vec<Move> {
+        self.neighbors()
+            .into_iter()
+            .filter(|(_, state)| state == goal)
+            .map(|(m, _)| m)
+            .collect()
+    }
+
+    /// Like `Display`, but with the x coordinate of every column above the grid and the y
+    /// coordinate of every row to its left, to make clear which position `get(x, y)` refers to.
+    pub fn to_labeled_string(&self) -> String {
+        let width = self.tile_width();
+        let label_width = (self.size().max(2) - 1).to_string().len();
+        let mut str = format!("{:label_width$} ", "");
+        for x in 0..self.size() {
+            str.push_str(&format!("  {:>width$} ", x));
+        }
+        str = str.trim_end().to_string();
+        str.push('\n');
+
+        for (y, row) in self.to_string().lines().enumerate() {
+            str.push_str(&format!("{:>label_width$} {}\n", y, row));
+        }
+        str
+    }
+
+    /// Looks up the optimal distance of this state in a table built by `build_distance_table`.
+    /// Returns None if the goal of the table cannot be reached from this state.
+    pub fn optimal_distance_from_table(&self, table: &DistanceTable) -> Option<u8> {
+        table.get(self)
+    }
+}
+
+/// Returns true if some sequence of moves leads from one state to the other. Only compares the
+/// tiles and their parity (see `GameState::parity`), so it is cheap to check before searching.
+pub fn reachable(from: &GameState, to: &GameState) -> bool {
+    from.can_reach(to)
+}
+
+// see GameState::search_key
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum SearchKey {
+    Packed(u64),
+    Tiles(Vec<u8>),
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other.
+/// Might run forever if there is no path, so use with caution!
+/// Returns an empty list right away if the states are equal.
+pub fn find_shortest_path(from: GameState, to: GameState) -> Vec<Move> {
+    breadth_first_search(from, to, true).0
+}
+
+// the search behind find_shortest_path, optionally without skipping the moves that undo the
+// previous one. Also returns how many successor states were generated, to measure the pruning.
+fn breadth_first_search(from: GameState, to: GameState, prune_inverse: bool) -> (Vec<Move>, usize) {
+    if from == to { return (vec![], 0); }
+    let mut generated = 0;
+
+    let mut paths: Vec::<Vec<Move>> = vec![vec![]];
+
+    // states are keys and values are the shortest paths to reach that state
+    let mut states_discovered: Map<SearchKey, bool> = Map::new();
+
+    for _i in 1..=1000 { 
+        let mut new_paths: Vec::<Vec<Move>> = vec![];
+        for path in paths {
+            let mut curr_state = from.clone();
+            curr_state.perform_moves(&path);
+            if curr_state == to { return (path, generated); }
+
+            for mv in curr_state.legal_moves() {
+                // undoing the last move leads back to a state that was already discovered
+                if prune_inverse && path.last() == Some(&mv.inverse()) { continue; }
+
+                let mut next_state = curr_state.clone();
+                next_state.perform_move(mv);
+                generated += 1;
+                if states_discovered.contains_key(&next_state.search_key()) {
+                    // ignore this move since we've already reached it once
+                    continue;
+                }
+
+                // add this state to our hashmap
+                states_discovered.insert(next_state.search_key(), true);
+
+                // add this path to our new paths
+                let mut new_path = path.clone();
+                new_path.push(mv);
+                if next_state == to { return (new_path, generated); }
+                new_paths.push(new_path);
+            };
+        };
+
+        paths = new_paths;
+        // println!("Paths for i = {}\n{:?}", i, paths);
+    };  
+
+    panic!("Did not find any valid path of any valid length");
+}
+
+/// Same as `find_shortest_path`, but also returns the cost of the path. Every move costs 1, so
+/// the cost is the number of moves.
+pub fn find_shortest_path_with_cost(from: GameState, to: GameState) -> (Vec<Move>, usize) {
+    let path = find_shortest_path(from, to);
+    let cost = path.len();
+    (path, cost)
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other, like
+/// `find_shortest_path`, but gives up once the deadline has passed. The clock is only checked
+/// every few hundred states, so the search may run slightly past the deadline.
+/// Returns None if the deadline passed or if there is no path.
+#[cfg(feature = "std")]
+pub fn find_shortest_path_deadline(from: GameState, to: GameState, deadline: std::time::Instant) -> Option<Vec<Move>> {
+    const CLOCK_INTERVAL: usize = 256;
+
+    let mut expanded = 0;
+    restricted_search(&from, &to, &Move::ALL, || {
+        expanded += 1;
+        (expanded - 1) % CLOCK_INTERVAL != 0 || std::time::Instant::now() < deadline
+    })
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other using only the
+/// allowed moves, for variants of the puzzle that forbid some directions.
+/// Returns None if there is no such path; this can take a long time when the allowed moves
+/// reach a large part of all states.
+pub fn find_shortest_path_restricted(from: GameState, to: GameState, allowed: &[Move]) -> Option<Vec<Move>> {
+    restricted_search(&from, &to, allowed, || true)
+}
+
+// breadth-first search that only performs the allowed moves. Gives up (returning None) as soon as
+// keep_going returns false, which is asked before every state that is expanded.
+fn restricted_search(from: &GameState, to: &GameState, allowed: &[Move], keep_going: impl FnMut() -> bool) -> Option<Vec<Move>> {
+    if from == to { return Some(vec![]); }
+    if !from.can_reach(to) { return None; }
+
+    layered_search(from, allowed, keep_going, |nodes, layer| {
+        layer.iter().find(|index| nodes[**index].0 == *to).map(|index| path_to(nodes, *index))
+    })
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other, like
+/// `find_shortest_path`, calling `on_layer` with the depth and the number of newly discovered
+/// states after each BFS layer, e.g. for printing progress. The last call has the depth of the
+/// solution.
+/// Returns None if there is no path.
+pub fn find_shortest_path_with_progress(from: GameState, to: GameState, mut on_layer: impl FnMut(usize, usize)) -> Option<Vec<Move>> {
+    if from == to { return Some(vec![]); }
+    if !from.can_reach(&to) { return None; }
+
+    let mut depth = 0;
+    layered_search(&from, &Move::ALL, || true, |nodes, layer| {
+        depth += 1;
+        on_layer(depth, layer.len());
+        layer.iter().find(|index| nodes[**index].0 == to).map(|index| path_to(nodes, *index))
+    })
+}
+
+/// Same as `find_shortest_path`, but expands the states of each BFS layer on multiple threads.
+/// New states are merged into the set of discovered states in the same order the sequential
+/// version discovers them, so both return the same path.
+#[cfg(feature = "rayon")]
+pub fn find_shortest_path_parallel(from: GameState, to: GameState) -> Vec<Move> {
+    use rayon::prelude::*;
+
+    if from == to { return vec![]; }
+
+    let mut states_discovered: Set<SearchKey> = Set::new();
+    states_discovered.insert(from.search_key());
+    let mut frontier: Vec<(GameState, Vec<Move>)> = vec![(from, vec![])];
+
+    while !frontier.is_empty() {
+        // the discovered states are only read while expanding, so the threads can share them
+        let expanded: Vec<Vec<(Move, GameState)>> = frontier
+            .par_iter()
+            .map(|(state, path)| {
+                state.neighbors().into_iter()
+                    // undoing the last move leads back to a state that was already discovered
+                    .filter(|(mv, _)| path.last() != Some(&mv.inverse()))
+                    .filter(|(_, next_state)| !states_discovered.contains(&next_state.search_key()))
+                    .collect()
+            })
+            .collect();
+
+        let mut new_frontier = vec![];
+        for ((_, path), neighbors) in frontier.iter().zip(expanded) {
+            for (mv, next_state) in neighbors {
+                // another state of this layer might have discovered it already
+                if !states_discovered.insert(next_state.search_key()) { continue; }
+
+                let mut new_path = path.clone();
+                new_path.push(mv);
+                if next_state == to { return new_path; }
+                new_frontier.push((next_state, new_path));
+            }
+        }
+        frontier = new_frontier;
+    }
+
+    panic!("Did not find any valid path of any valid length");
+}
+
+/// Every state a search discovered, paired with the state it was discovered from, see
+/// `find_shortest_path_traced`.
+pub type Trace = Vec<(GameState, Option<GameState>)>;
+
+/// Same as `find_shortest_path`, but also returns every state the search discovered, in the
+/// order they were discovered, paired with the state it was discovered from (None for `from`).
+/// Every state appears only once.
+/// Returns None right away if there is no path.
+pub fn find_shortest_path_traced(from: GameState, to: GameState) -> Option<(Vec<Move>, Trace)> {
+    if !from.can_reach(&to) { return None; }
+
+    let mut traced: Vec<(GameState, Option<GameState>)> = vec![(from.clone(), None)];
+    // for every entry in traced: the index of its parent and the move that led to it
+    let mut parents: Vec<Option<(usize, Move)>> = vec![None];
+    let mut states_discovered: Set<SearchKey> = Set::new();
+    states_discovered.insert(from.search_key());
+
+    let mut goal_index = if from == to { Some(0) } else { None };
+    let mut queue: VecDeque<usize> = VecDeque::from([0]);
+    while goal_index.is_none() {
+        // to is reachable, so it is discovered before the queue runs dry
+        let index = queue.pop_front()?;
+        let state = traced[index].0.clone();
+        let last = parents[index].map(|(_, mv)| mv);
+        for mv in Move::ALL {
+            // undoing the last move leads back to a state that was already discovered
+            if last == Some(mv.inverse()) { continue; }
+            let Some(next_state) = state.neighbor_for(mv) else { continue; };
+            if !states_discovered.insert(next_state.search_key()) { continue; }
+
+            traced.push((next_state.clone(), Some(state.clone())));
+            parents.push(Some((index, mv)));
+            queue.push_back(traced.len() - 1);
+            if next_state == to {
+                goal_index = Some(traced.len() - 1);
+                break;
+            }
+        }
+    }
+
+    let mut path = vec![];
+    let mut index = goal_index.unwrap();
+    while let Some((parent, mv)) = parents[index] {
+        path.push(mv);
+        index = parent;
+    }
+    path.reverse();
+    Some((path, traced))
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other, using iterative
+/// deepening A* with the Manhattan distance as heuristic. Only keeps the current path in memory,
+/// so it can solve much harder states than `find_shortest_path`.
+/// Returns None if there is no path, or if the path would need more than `MAX_OPTIMAL_MOVES` on
+/// a board that is at most 4x4 (which would mean there is a bug). Boards larger than 4x4 are not
+/// capped.
+pub fn find_shortest_path_idastar(from: GameState, to: GameState) -> Option<Vec<Move>> {
+    find_shortest_path_idastar_with(from, to, None)
+}
+
+/// Same as `find_shortest_path_idastar`, but optionally uses a pattern database instead of the
+/// Manhattan distance as heuristic, which needs far fewer expansions on hard states.
+/// Panics if the database was built for a goal other than `to`.
+pub fn find_shortest_path_idastar_with(from: GameState, to: GameState, pdb: Option<&PatternDatabase>) -> Option<Vec<Move>> {
+    if from == to { return Some(vec![]); }
+    if !from.can_reach(&to) { return None; }
+    if let Some(pdb) = pdb {
+        assert!(*pdb.goal() == to, "the pattern database was built for a different goal state");
+    }
+
+    let heuristic = |state: &GameState| match pdb {
+        Some(pdb) => pdb.estimate(state),
+        None => state.manhattan_distance(&to),
+    };
+
+    // nothing up to 4x4 needs more moves, so a larger threshold means the search can't succeed
+    let max_threshold = if to.size() <= 4 { MAX_OPTIMAL_MOVES } else { u32::MAX };
+    idastar_deepen(from, &to, max_threshold, &heuristic)
+}
+
+// raises the threshold of idastar_search until the goal is found, or until the threshold would
+// exceed max_threshold
+fn idastar_deepen(from: GameState, to: &GameState, max_threshold: u32, heuristic: &impl Fn(&GameState) -> u32) -> Option<Vec<Move>> {
+    let mut state = from;
+    let mut path = vec![];
+    let mut threshold = heuristic(&state);
+    while threshold <= max_threshold {
+        match idastar_search(&mut state, to, &mut path, threshold, heuristic) {
+            None => return Some(path),
+            Some(next_threshold) => threshold = next_threshold,
+        }
+    }
+    None
+}
+
+/// The number of moves that is enough to solve every solvable 4x4 board optimally. Smaller boards
+/// need far fewer (at most 6 for 2x2 and 31 for 3x3), so the cap holds for them too. Larger
+/// boards can need more, so `find_shortest_path_idastar` doesn't cap their search at all.
+pub const MAX_OPTIMAL_MOVES: u32 = 80;
+
+// depth-first search that gives up on paths whose cost plus estimate exceeds the threshold.
+// Returns None if the goal was found (the solution is left in path), otherwise the smallest
+// cost plus estimate that exceeded the threshold.
+fn idastar_search(state: &mut GameState, goal: &GameState, path: &mut Vec<Move>, threshold: u32, heuristic: &impl Fn(&GameState) -> u32) -> Option<u32> {
+    let estimate = path.len() as u32 + heuristic(state);
+    if estimate > threshold { return Some(estimate); }
+    if state == goal { return None; }
+
+    let mut min_exceeded = u32::MAX;
+    for mv in Move::ALL {
+        // undoing the last move can never be part of a shortest path
+        if path.last() == Some(&mv.inverse()) { continue; }
+        if !state.perform_move(mv) { continue; }
+
+        path.push(mv);
+        match idastar_search(state, goal, path, threshold, heuristic) {
+            None => return None,
+            Some(exceeded) => min_exceeded = min_exceeded.min(exceeded),
+        }
+        path.pop();
+        state.perform_move(mv.inverse());
+    }
+    Some(min_exceeded)
+}
+
+/// States whose optimal solution needs at most this many moves are `Difficulty::Easy`.
+pub const EASY_MAX_DISTANCE: usize = 10;
+
+/// States whose optimal solution needs at most this many moves (and that aren't easy) are
+/// `Difficulty::Medium`; all solvable states beyond that are `Difficulty::Hard`.
+pub const MEDIUM_MAX_DISTANCE: usize = 20;
+
+/// Which of the two classes of mutually reachable boards a board belongs to, see
+/// `GameState::parity`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+/// How hard it is to solve a state, see `GameState::difficulty`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Unsolvable,
+}
+
+/// How long a breadth-first search between two states is expected to take, see
+/// `estimate_solve_difficulty`. Only a rough guess, since the real number of expanded states
+/// depends on more than the Manhattan distance.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DifficultyEstimate {
+    /// Fewer than 10 thousand states are expected to be expanded, well below a second.
+    Fast,
+    /// Fewer than a million states are expected to be expanded, a few seconds at most.
+    Moderate,
+    /// A million states or more are expected to be expanded. With a Manhattan distance of 30 or
+    /// more the search may not finish at all.
+    Slow,
+    /// There is no path, which `find_shortest_path` is not able to tell.
+    Unsolvable,
+}
+
+/// A source of random numbers, e.g. for generating random states.
+///
+/// This is used instead of `rand::Rng` so the crate doesn't depend on `rand` (and keeps working
+/// without std); the name is different so the two don't clash when both are imported. Any `rand`
+/// generator can be used through a one-line impl that forwards to `rand::RngCore::next_u64`.
+pub trait RandomSource {
+    /// Returns the next random number.
+    fn next_u64(&mut self) -> u64;
+}
+
+/// A small and fast pseudo-random number generator (xorshift64). Always produces the same
+/// numbers for the same seed.
+#[derive(Debug, Clone)]
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    /// Creates a generator from the given seed.
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on 0, so that seed is replaced by an arbitrary non-zero constant
+        Self { state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed } }
+    }
+}
+
+impl RandomSource for XorShift64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+// follows the parent links of a search tree from the node back to the root, returning the moves
+// that lead from the root to the node
+fn path_to(nodes: &[(GameState, Option<(usize, Move)>)], index: usize) -> Vec<Move> {
+    let mut path = vec![];
+    let mut index = index;
+    while let Some((parent, mv)) = nodes[index].1 {
+        path.push(mv);
+        index = parent;
+    }
+    path.reverse();
+    path
+}
+
+// breadth-first search from the state that only performs the allowed moves, one layer at a time.
+// Every discovered state is stored once, with the index of its parent and the move that led to
+// it. After each layer, on_layer is called with every state so far and the indices of the ones
+// in that layer, and the search stops as soon as it returns Some. Gives up (returning None) as
+// soon as keep_going returns false, which is asked before every state that is expanded, or once
+// every reachable state has been discovered.
+fn layered_search<R>(
+    from: &GameState,
+    allowed: &[Move],
+    mut keep_going: impl FnMut() -> bool,
+    mut on_layer: impl FnMut(&[(GameState, Option<(usize, Move)>)], &[usize]) -> Option<R>,
+) -> Option<R> {
+    let mut nodes: Vec<(GameState, Option<(usize, Move)>)> = vec![(from.clone(), None)];
+    let mut states_discovered: Set<SearchKey> = Set::from([from.search_key()]);
+    let mut layer = vec![0];
+
+    while !layer.is_empty() {
+        let mut next_layer = vec![];
+        for index in layer {
+            if !keep_going() { return None; }
+
+            let state = nodes[index].0.clone();
+            let last = nodes[index].1.map(|(_, mv)| mv);
+            for mv in Move::ALL {
+                // undoing the last move leads back to a state that was already discovered
+                if !allowed.contains(&mv) || last == Some(mv.inverse()) { continue; }
+                let Some(next_state) = state.neighbor_for(mv) else { continue; };
+                if !states_discovered.insert(next_state.search_key()) { continue; }
+                nodes.push((next_state, Some((index, mv))));
+                next_layer.push(nodes.len() - 1);
+            }
+        }
+        // finish the layer first, so the callback always sees whole layers
+        if let Some(result) = on_layer(&nodes, &next_layer) { return Some(result); }
+        layer = next_layer;
+    }
+    None
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other, using A* with
+/// the Manhattan distance as heuristic.
+/// Returns None if there is no path.
+pub fn find_shortest_path_astar(from: GameState, to: GameState) -> Option<Vec<Move>> {
+    astar_search(&from, &to, |state| state.manhattan_distance(&to)).map(|(path, _)| path)
+}
+
+/// Yields every state along a shortest path, starting with `from` and ending with `to`, e.g. for
+/// animating the solution. The path is found with `find_shortest_path` right away; nothing is
+/// yielded if there is no path.
+pub fn solve_states(from: GameState, to: GameState) -> impl Iterator<Item = GameState> {
+    let reachable = from.can_reach(&to);
+    let moves = if reachable { find_shortest_path(from.clone(), to) } else { vec![] };
+    let start = if reachable { Some(from.clone()) } else { None };
+    let mut state = from;
+    start.into_iter().chain(moves.into_iter().map(move |m| {
+        state.perform_move(m);
+        state.clone()
+    }))
+}
+
+/// Finds the nearest of several goal states and the shortest path to it. Returns the index of
+/// that goal in `goals` together with the path; if several goals are equally near, the one
+/// with the lowest index wins.
+///
+/// Panics if none of the goals can be reached.
+pub fn find_shortest_path_to_any(from: GameState, goals: &[GameState]) -> (usize, Vec<Move>) {
+    // the lowest index of every reachable goal, by its packed state
+    let mut targets: BTreeMap<SearchKey, usize> = BTreeMap::new();
+    for (index, goal) in goals.iter().enumerate().rev() {
+        if from.can_reach(goal) { targets.insert(goal.search_key(), index); }
+    }
+    assert!(!targets.is_empty(), "none of the goal states can be reached");
+    if let Some(index) = targets.get(&from.search_key()) { return (*index, vec![]); }
+
+    layered_search(&from, &Move::ALL, || true, |nodes, layer| {
+        // look at the whole layer, so that equally near goals are decided by their index
+        let (goal, index) = layer.iter()
+            .filter_map(|index| targets.get(&nodes[*index].0.search_key()).map(|goal| (*goal, *index)))
+            .min()?;
+        Some((goal, path_to(nodes, index)))
+    }).expect("a reachable goal is always discovered")
+}
+
+/// Finds every shortest path from one state to the other. Returns a list with only the empty
+/// path if both states are equal, and an empty list if there is no path.
+/// The number of shortest paths can grow exponentially with their length.
+pub fn find_all_shortest_paths(from: GameState, to: GameState) -> Vec<Vec<Move>> {
+    if from == to { return vec![vec![]]; }
+    if !from.can_reach(&to) { return vec![]; }
+
+    let mut parents: Predecessors = Map::from([(from.search_key(), (0, vec![]))]);
+    let goal = to.search_key();
+    let mut layer = vec![from];
+    let mut depth = 0;
+    while !parents.contains_key(&goal) {
+        depth += 1;
+        let mut next_layer = vec![];
+        for state in &layer {
+            let key = state.search_key();
+            // undoing a move that led here goes back to the previous layer
+            let undoing: Vec<Move> = parents[&key].1.iter().map(|(_, mv)| mv.inverse()).collect();
+            for mv in Move::ALL {
+                if undoing.contains(&mv) { continue; }
+                let Some(next_state) = state.neighbor_for(mv) else { continue; };
+                match parents.get_mut(&next_state.search_key()) {
+                    Some((next_depth, predecessors)) => {
+                        if *next_depth == depth { predecessors.push((key.clone(), mv)); }
+                    }
+                    None => {
+                        parents.insert(next_state.search_key(), (depth, vec![(key.clone(), mv)]));
+                        next_layer.push(next_state);
+                    }
+                }
+            }
+        }
+        layer = next_layer;
+    }
+
+    let mut paths = vec![];
+    collect_paths(&parents, &goal, &mut vec![], &mut paths);
+    paths
+}
+
+// for every discovered state, how many moves it takes to reach it and every state (and the move
+// from there) it can be reached from with one move less
+type Predecessors = Map<SearchKey, (usize, Vec<(SearchKey, Move)>)>;
+
+// walks back from the state to the start along every recorded predecessor, adding each path
+// (with the moves collected so far reversed) to the list
+fn collect_paths(parents: &Predecessors, key: &SearchKey, moves: &mut Vec<Move>, paths: &mut Vec<Vec<Move>>) {
+    let predecessors = &parents[key].1;
+    if predecessors.is_empty() {
+        paths.push(moves.iter().rev().copied().collect());
+        return;
+    }
+    for (parent, mv) in predecessors {
+        moves.push(*mv);
+        collect_paths(parents, parent, moves, paths);
+        moves.pop();
+    }
+}
+
+/// Finds the minimal number of moves needed to get from one state to the other for boards with
+/// any kind of tiles, by numbering them with `GameState::relabel` and solving with
+/// `find_shortest_path_idastar`. Returns None if there is no path.
+pub fn find_shortest_path_labeled<T: Tile>(from: GameState<T>, to: GameState<T>) -> Option<Vec<Move>> {
+    let numbered_from = from.relabel(&to)?;
+    let numbered_to = to.relabel(&to)?;
+    find_shortest_path_idastar(numbered_from, numbered_to)
+}
+
+/// Finds a path from one state to the other with A*, multiplying the Manhattan distance by
+/// `weight`. With a weight of 1 the path is the shortest one; larger weights expand fewer
+/// states, but the path may be up to `weight` times longer than the shortest.
+/// Returns an empty list if there is no path.
+///
+/// Panics if the weight is negative or not a number.
+pub fn find_path_weighted_astar(from: GameState, to: GameState, weight: f64) -> Vec<Move> {
+    assert!(weight >= 0.0, "weight must not be negative");
+    // rounded to the nearest whole move, since the costs are counted in moves
+    let heuristic = |state: &GameState| (weight * state.manhattan_distance(&to) as f64 + 0.5) as u32;
+    astar_search(&from, &to, heuristic).map(|(path, _)| path).unwrap_or_default()
+}
+
+/// Finds a path from one state to the other that keeps the distance travelled by the empty
+/// position as small as possible, using Dijkstra's algorithm. The empty position moves by one cell
+/// with every move, so this is a shortest path; see `find_path_min_cost` for other cost models.
+/// Returns an empty list if there is no path.
+pub fn find_path_min_blank_travel(from: GameState, to: GameState) -> Vec<Move> {
+    find_path_min_cost(from, to, |_| 1).unwrap_or_default()
+}
+
+/// Finds a path from one state to the other with the lowest total cost, where the cost of every
+/// move is given by `cost`, using Dijkstra's algorithm.
+/// Returns None if there is no path.
+pub fn find_path_min_cost(from: GameState, to: GameState, cost: impl Fn(Move) -> u32) -> Option<Vec<Move>> {
+    weighted_search(&from, &to, cost, |_| 0).map(|(path, _)| path)
+}
+
+// A* search, always expanding the state with the lowest cost plus estimate next (the deepest one
+// on ties). Returns the path and the number of states that were expanded.
+fn astar_search(from: &GameState, to: &GameState, heuristic: impl Fn(&GameState) -> u32) -> Option<(Vec<Move>, usize)> {
+    weighted_search(from, to, |_| 1, heuristic)
+}
+
+// A* search where every move costs what `move_cost` returns for it; Dijkstra's algorithm if the
+// heuristic is always 0.
+fn weighted_search(from: &GameState, to: &GameState, move_cost: impl Fn(Move) -> u32, heuristic: impl Fn(&GameState) -> u32) -> Option<(Vec<Move>, usize)> {
+    if from == to { return Some((vec![], 0)); }
+    if !from.can_reach(to) { return None; }
+
+    // every state that was pushed, with the index of its parent and the move that led to it
+    let mut nodes: Vec<(GameState, Option<(usize, Move)>)> = vec![(from.clone(), None)];
+    let mut best_costs: Map<SearchKey, usize> = Map::from([(from.search_key(), 0)]);
+    let mut open = BinaryHeap::from([(Reverse(heuristic(from) as usize), 0, Reverse(0))]);
+    let mut expanded = 0;
+
+    while let Some((_, cost, Reverse(index))) = open.pop() {
+        let state = nodes[index].0.clone();
+        // a cheaper way to this state was found after this one was pushed
+        if best_costs[&state.search_key()] < cost { continue; }
+
+        if state == *to { return Some((path_to(&nodes, index), expanded)); }
+
+        expanded += 1;
+        for (mv, next_state) in state.neighbors() {
+            let key = next_state.search_key();
+            let next_cost = cost + move_cost(mv) as usize;
+            if best_costs.get(&key).is_some_and(|best| *best <= next_cost) { continue; }
+
+            best_costs.insert(key, next_cost);
+            let estimate = next_cost + heuristic(&next_state) as usize;
+            nodes.push((next_state, Some((index, mv))));
+            open.push((Reverse(estimate), next_cost, Reverse(nodes.len() - 1)));
+        }
+    }
+    None
+}
+
+/// Finds some path from one state to the other, always expanding the state with the lowest
+/// Manhattan distance to the goal next, no matter how many moves it took to get there. Usually
+/// much faster than the optimal solvers on hard states, but the path may be far from the shortest.
+/// Returns None if there is no path.
+pub fn find_path_greedy(from: GameState, to: GameState) -> Option<Vec<Move>> {
+    if from == to { return Some(vec![]); }
+    if !from.can_reach(&to) { return None; }
+
+    // every state that was pushed, with the index of its parent and the move that led to it
+    let mut nodes: Vec<(GameState, Option<(usize, Move)>)> = vec![(from.clone(), None)];
+    let mut states_discovered: Set<SearchKey> = Set::from([from.search_key()]);
+    let mut open = BinaryHeap::from([(Reverse(from.manhattan_distance(&to)), Reverse(0))]);
+
+    while let Some((_, Reverse(index))) = open.pop() {
+        let state = nodes[index].0.clone();
+        if state == to { return Some(path_to(&nodes, index)); }
+
+        for (mv, next_state) in state.neighbors() {
+            if !states_discovered.insert(next_state.search_key()) { continue; }
+            let estimate = next_state.manhattan_distance(&to);
+            nodes.push((next_state, Some((index, mv))));
+            open.push((Reverse(estimate), Reverse(nodes.len() - 1)));
+        }
+    }
+    None
+}
+
+/// Runs A* with every built-in heuristic, returning for each its name, its estimate for `from`,
+/// and how many states A* expanded with it. The expansion count is 0 if `to` can't be reached.
+pub fn compare_heuristics(from: GameState, to: GameState) -> Vec<(&'static str, u32, usize)> {
+    let expanded = |heuristic: &dyn Fn(&GameState) -> u32| {
+        astar_search(&from, &to, heuristic).map_or(0, |(_, expanded)| expanded)
+    };
+
+    vec![
+        ("manhattan distance", from.manhattan_distance(&to), expanded(&|state| state.manhattan_distance(&to))),
+        ("misplaced tiles", from.misplaced_tiles(&to), expanded(&|state| state.misplaced_tiles(&to))),
+    ]
+}
+
+/// Runs a BFS from the goal that stops after `max_depth` moves, and returns one of the states
+/// farthest away from the goal together with its distance. If `max_depth` is large enough to
+/// cover every reachable state, this is a hardest state of the puzzle.
+pub fn hardest_state_within(goal: &GameState, max_depth: usize) -> (GameState, usize) {
+    let mut states_discovered: Set<SearchKey> = Set::from([goal.search_key()]);
+    let mut layer = vec![goal.clone()];
+    let mut depth = 0;
+
+    while depth < max_depth {
+        let mut next_layer = vec![];
+        for state in &layer {
+            for (_, next_state) in state.neighbors() {
+                if states_discovered.insert(next_state.search_key()) { next_layer.push(next_state); }
+            }
+        }
+        // every reachable state was discovered
+        if next_layer.is_empty() { break; }
+
+        layer = next_layer;
+        depth += 1;
+    }
+
+    (layer.swap_remove(0), depth)
+}
+
+/// Scrambles the default position `samples` times with `scramble_moves` random moves each, and
+/// counts how many moves an optimal solution of each scramble needs. Returns (distance, count)
+/// pairs sorted by distance. The same seed always produces the same histogram.
+pub fn scramble_distance_histogram(samples: usize, scramble_moves: usize, seed: u64) -> Vec<(usize, usize)> {
+    let goal = GameState::default();
+    let mut rng = XorShift64::new(seed);
+    let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+    for _ in 0..samples {
+        let mut state = goal.clone();
+        state.scramble_seeded(scramble_moves, rng.next_u64());
+        let distance = state.optimal_distance(&goal).expect("scrambles are always solvable");
+        *histogram.entry(distance).or_insert(0) += 1;
+    }
+    histogram.into_iter().collect()
+}
+
+/// The optimal distance to a goal of every state that can reach it, see `build_distance_table`.
+#[derive(Debug, Clone)]
+pub struct DistanceTable {
+    size: usize,
+    // keyed by the packed states, see `GameState::pack`
+    distances: Map<u64, u8>,
+}
+
+impl DistanceTable {
+    /// Returns the optimal distance of the state to the goal, or None if the goal cannot be
+    /// reached from it (including states of another size than the goal).
+    pub fn get(&self, state: &GameState) -> Option<u8> {
+        if state.size() != self.size { return None; }
+        self.distances.get(&state.pack()).copied()
+    }
+
+    /// Returns the number of states in the table.
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Returns true if the table contains no states, which never happens for built tables.
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    /// Returns the largest distance in the table, i.e. how many moves the hardest states need.
+    pub fn max_distance(&self) -> u8 {
+        self.distances.values().copied().max().unwrap_or(0)
+    }
+}
+
+/// Computes the optimal distance to the goal of every state that can reach it, with a breadth-first
+/// search backwards from the goal. Only meant for 3x3 boards: the table has 181440 entries, and
+/// larger boards have far too many states.
+/// Look up states with `GameState::optimal_distance_from_table` or `DistanceTable::get`.
+///
+/// Panics if the goal is not a 3x3 board.
+pub fn build_distance_table(goal: &GameState) -> DistanceTable {
+    assert_eq!(goal.size(), 3, "distance tables can only be built for 3x3 boards");
+    let mut distances = Map::from([(goal.pack(), 0)]);
+    let mut queue = VecDeque::from([(goal.clone(), 0)]);
+    while let Some((state, distance)) = queue.pop_front() {
+        for (_, next_state) in state.neighbors() {
+            let key = next_state.pack();
+            if distances.contains_key(&key) { continue; }
+            distances.insert(key, distance + 1);
+            queue.push_back((next_state, distance + 1));
+        }
+    }
+    DistanceTable{size: goal.size(), distances}
+}
+
+/// Guesses how hard solving from one state to the other with `find_shortest_path` will be,
+/// without searching. The search has to go at least as deep as the Manhattan distance, and each
+/// level has about 2.13 times as many states as the one before (the average branching factor
+/// without undoing the last move), so about 2.13^distance states are expanded. The estimate is
+/// rounded to the nearest state, which makes a Manhattan distance of up to 12 (about 8721
+/// states) `Fast`, 13 to 18 (about 814 thousand states) `Moderate`, and anything beyond `Slow`.
+pub fn estimate_solve_difficulty(from: &GameState, to: &GameState) -> DifficultyEstimate {
+    if !from.can_reach(to) { return DifficultyEstimate::Unsolvable; }
+
+    match estimated_expansions(from.manhattan_distance(to)) {
+        0..10_000 => DifficultyEstimate::Fast,
+        10_000..1_000_000 => DifficultyEstimate::Moderate,
+        _ => DifficultyEstimate::Slow,
+    }
+}
+
+// returns 2.13^distance rounded to the nearest integer, computed in fixed point with six decimal
+// places so the rounding errors of the single steps don't add up
+fn estimated_expansions(distance: u32) -> u64 {
+    const SCALE: u128 = 1_000_000;
+    let mut scaled = SCALE;
+    for _ in 0..distance {
+        scaled = scaled.saturating_mul(213) / 100;
+    }
+    u64::try_from((scaled + SCALE / 2) / SCALE).unwrap_or(u64::MAX)
+}
+
+/// Remembers shortest paths that were already computed, so solving the same pair of states
+/// again is free.
+#[derive(Debug, Default)]
+pub struct PathCache {
+    paths: Map<(GameState, GameState), Vec<Move>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl PathCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shortest path from one state to the other, running `find_shortest_path` only
+    /// if this pair has not been solved before.
+    pub fn solve(&mut self, from: GameState, to: GameState) -> Vec<Move> {
+        let key = (from, to);
+        if let Some(path) = self.paths.get(&key) {
+            self.hits += 1;
+            return path.clone();
+        }
+
+        self.misses += 1;
+        let path = find_shortest_path(key.0.clone(), key.1.clone());
+        self.paths.insert(key, path.clone());
+        path
+    }
+
+    /// Returns how many calls to `solve` were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns how many calls to `solve` had to run the solver.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+/// A state that keeps track of its Manhattan distance to a goal state. Performing a move only
+/// looks at the tile that moved, instead of all tiles like `GameState::manhattan_distance`.
+#[derive(Debug, Clone)]
+pub struct TrackedState {
+    state: GameState,
+    // the position of every tile in the goal state, indexed by tile
+    goal_positions: Vec<(u8, u8)>,
+    distance: u32,
+}
+
+impl TrackedState {
+    /// Starts tracking the distance from the state to the goal state.
+    /// The goal state must contain the same tiles.
+    pub fn new(state: GameState, goal: &GameState) -> Self {
+        let mut goal_positions = vec![(0, 0); goal.size() * goal.size()];
+        for (y, x, tile) in goal.cells() {
+            if let Some(tile) = tile { goal_positions[tile as usize] = (x, y); }
+        }
+        let distance = state.manhattan_distance(goal);
+        Self { state, goal_positions, distance }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the Manhattan distance from the current state to the goal state.
+    pub fn manhattan_distance(&self) -> u32 {
+        self.distance
+    }
+
+    /// Performs the move like `GameState::perform_move` and updates the distance. Returns false
+    /// if the move was not possible.
+    pub fn perform_move(&mut self, m: Move) -> bool {
+        let Some(to) = self.state.blank_position() else { return false; };
+        let Some(from) = self.state.tile_position_next_to(to, m) else { return false; };
+        let tile = self.state.get(from.0, from.1).unwrap();
+        let goal = self.goal_positions[tile as usize];
+        let distance = |(x, y): (u8, u8)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u32;
+
+        self.distance = self.distance - distance(from) + distance(to);
+        self.state.swap(to.0, to.1, from.0, from.1);
+        true
+    }
+}
+
+/// A puzzle together with the moves that were made on it, e.g. for saving and replaying a solve.
+/// Can be serialized with the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveSession {
+    pub initial: GameState,
+    pub moves: Vec<Move>,
+}
+
+impl SolveSession {
+    /// Starts a session without any moves.
+    pub fn new(initial: GameState) -> Self {
+        Self { initial, moves: vec![] }
+    }
+
+    /// Adds the move to the session if it is possible in the current state. Returns false (and
+    /// doesn't record anything) otherwise.
+    pub fn record(&mut self, m: Move) -> bool {
+        if !self.final_state().can_move(m) { return false; }
+        self.moves.push(m);
+        true
+    }
+
+    /// Returns the state after performing all recorded moves on the initial state.
+    pub fn final_state(&self) -> GameState {
+        let mut state = self.initial.clone();
+        state.perform_moves(&self.moves);
+        state
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+    BottomToTop,
+}
+
+impl Move {
+    /// All moves, in the order the solvers try them.
+    pub const ALL: [Move; 4] = [Move::LeftToRight, Move::RightToLeft, Move::TopToBottom, Move::BottomToTop];
+
+    /// Returns the move that undoes this move.
+    pub fn inverse(self) -> Move {
+        match self {
+            Move::LeftToRight => Move::RightToLeft,
+            Move::RightToLeft => Move::LeftToRight,
+            Move::TopToBottom => Move::BottomToTop,
+            Move::BottomToTop => Move::TopToBottom,
+        }
+    }
+
+    /// Returns a description of the move for logs and UIs, named after the direction the tile
+    /// slides in: "slide right" for `LeftToRight`, "slide left" for `RightToLeft`, "slide down"
+    /// for `TopToBottom` and "slide up" for `BottomToTop`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Move::LeftToRight => "slide right",
+            Move::RightToLeft => "slide left",
+            Move::TopToBottom => "slide down",
+            Move::BottomToTop => "slide up",
+        }
+    }
+
+    /// Returns an arrow pointing in the direction the tile slides in.
+    pub fn arrow(self) -> char {
+        match self {
+            Move::LeftToRight => '→',
+            Move::RightToLeft => '←',
+            Move::TopToBottom => '↓',
+            Move::BottomToTop => '↑',
+        }
+    }
+
+    /// Returns how far the empty position moves as (rows, columns) when the move is performed,
+    /// where rows grow downwards and columns grow to the right. The tile moves the opposite way,
+    /// so `LeftToRight` moves the empty position one column to the left: (0, -1).
+    pub fn delta(self) -> (i8, i8) {
+        match self {
+            Move::LeftToRight => (0, -1),
+            Move::RightToLeft => (0, 1),
+            Move::TopToBottom => (-1, 0),
+            Move::BottomToTop => (1, 0),
+        }
+    }
+}
+
+/// Returns the (x, y) position of the tile in the solved board of the given size (see
+/// `GameState::solved`), where tile n is the n-th cell in row-major order.
+/// Tile 0 stands for the empty position, which belongs in the bottom right corner.
+pub fn goal_position(tile: u8, size: usize) -> (u8, u8) {
+    let index = if tile == 0 { size * size - 1 } else { tile as usize - 1 };
+    ((index % size) as u8, (index / size) as u8)
+}
+
+/// Returns the solved board of the given size (see `GameState::solved`) in the format produced by
+/// `Display`, without building the state first.
+pub fn goal_string(size: usize) -> String {
+    let cells = size * size;
+    let width = cells.saturating_sub(1).to_string().len();
+    let mut str = String::new();
+    for index in 0..cells {
+        if index + 1 < cells {
+            str.push_str(&format!("| {:>width$} ", index + 1));
+        } else {
+            str.push_str(&format!("| {:width$} ", ""));
+        }
+        if index % size == size - 1 { str.push_str("|\n"); }
+    }
+    str
+}
+
+/// The error returned when a string contains a character that is not a move, see `parse_moves`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMoveError {
+    /// The index of the offending character (in characters, not bytes).
+    pub index: usize,
+    /// The offending character.
+    pub found: char,
+}
+
+impl core::fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid move '{}' at index {}", self.found, self.index)
+    }
+}
+
+impl core::error::Error for ParseMoveError {}
+
+/// Parses a sequence of moves written as letters for the direction the tile slides:
+/// `U` (BottomToTop), `D` (TopToBottom), `L` (RightToLeft) and `R` (LeftToRight).
+/// Lowercase letters are accepted and whitespace is ignored.
+pub fn parse_moves(s: &str) -> Result<Vec<Move>, ParseMoveError> {
+    s.chars()
+        .enumerate()
+        .filter(|(_, c)| !c.is_whitespace())
+        .map(|(index, c)| match c.to_ascii_uppercase() {
+            'U' => Ok(Move::BottomToTop),
+            'D' => Ok(Move::TopToBottom),
+            'L' => Ok(Move::RightToLeft),
+            'R' => Ok(Move::LeftToRight),
+            _ => Err(ParseMoveError { index, found: c }),
+        })
+        .collect()
+}
+
+/// Removes every move that is directly undone by the next one, until no such pair is left.
+/// The result has the same effect as the original moves on every state where all original
+/// moves are possible.
+pub fn simplify_moves(moves: &[Move]) -> Vec<Move> {
+    let mut simplified: Vec<Move> = vec![];
+    for m in moves {
+        // removing a pair can make the moves around it cancel too, which the stack handles
+        if simplified.last() == Some(&m.inverse()) {
+            simplified.pop();
+        } else {
+            simplified.push(*m);
+        }
+    }
+    simplified
+}
+
+/// Returns the arrow key a player would press for every move, see `Direction::arrow`. The arrow
+/// keys move the empty position, so `TopToBottom` is pressed as '↑'.
+pub fn moves_to_key_events(moves: &[Move]) -> Vec<char> {
+    moves.iter().map(|m| Direction::from_move(*m).arrow()).collect()
+}
+
+/// The rotations and reflections of the square, see `GameState::transform`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// A quarter turn clockwise, like `GameState::rotate_cw`.
+    RotateCw,
+    Rotate180,
+    /// A quarter turn counterclockwise, like `GameState::rotate_ccw`.
+    RotateCcw,
+    /// Left to right, like `GameState::mirror_horizontal`.
+    MirrorHorizontal,
+    /// Along the diagonal from the top left to the bottom right corner.
+    MirrorDiagonal,
+    /// Top to bottom, like `GameState::mirror_vertical`.
+    MirrorVertical,
+    /// Along the diagonal from the top right to the bottom left corner.
+    MirrorAntiDiagonal,
+}
+
+impl Symmetry {
+    /// All symmetries, the rotations first.
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity, Symmetry::RotateCw, Symmetry::Rotate180, Symmetry::RotateCcw,
+        Symmetry::MirrorHorizontal, Symmetry::MirrorDiagonal, Symmetry::MirrorVertical, Symmetry::MirrorAntiDiagonal,
+    ];
+}
+
+/// Which rules `GameState::perform_move_mode` follows at the edges of the board.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MoveMode {
+    /// Moves that would slide a tile in from outside the board are not possible.
+    Standard,
+    /// The board wraps around, so the tile comes in from the opposite edge.
+    Torus,
+}
+
+/// A direction on the screen, for moving the empty position like the arrow keys of most games
+/// do. Note that `Move` names the direction of the tile instead, which is the opposite.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Returns the move that takes the empty position one step in this direction.
+    pub fn to_move(self) -> Move {
+        match self {
+            Direction::Up => Move::TopToBottom,
+            Direction::Down => Move::BottomToTop,
+            Direction::Left => Move::LeftToRight,
+            Direction::Right => Move::RightToLeft,
+        }
+    }
+
+    /// Returns the direction the empty position takes when the move is performed.
+    pub fn from_move(m: Move) -> Direction {
+        match m {
+            Move::TopToBottom => Direction::Up,
+            Move::BottomToTop => Direction::Down,
+            Move::LeftToRight => Direction::Left,
+            Move::RightToLeft => Direction::Right,
+        }
+    }
+
+    /// Returns the arrow pointing in this direction.
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '↑',
+            Direction::Down => '↓',
+            Direction::Left => '←',
+            Direction::Right => '→',
+        }
+    }
+}
+
+// the URL-safe base64 alphabet, used by to_token
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // replaces tiles without checking the invariants like `set` does, to build invalid boards
+    fn corrupted(state: &GameState, tiles: &[((u8, u8), Option<u8>)]) -> GameState {
+        let mut board = state.board.clone();
+        for ((x, y), tile) in tiles {
+            board[*x as usize][*y as usize] = *tile;
+        }
+        GameState{board}
+    }
+
+    #[test]
+    fn test_default_game_state() {
+        let state = GameState::default();
+        assert_eq!(state.get(0, 0), Some(1));
+        assert_eq!(state.get(1, 0), Some(2));
+        assert_eq!(state.get(2, 0), Some(3));
+        assert_eq!(state.get(3, 0), Some(4));
+        assert_eq!(state.get(0, 1), Some(5));
+        assert_eq!(state.get(1, 1), Some(6));
+        assert_eq!(state.get(2, 1), Some(7));
+        assert_eq!(state.get(3, 1), Some(8));
+        assert_eq!(state.get(0, 2), Some(9));
+        assert_eq!(state.get(1, 2), Some(10));
+        assert_eq!(state.get(2, 2), Some(11));
+        assert_eq!(state.get(3, 2), Some(12));
+        assert_eq!(state.get(0, 3), Some(13));
+        assert_eq!(state.get(1, 3), Some(14));
+        assert_eq!(state.get(2, 3), Some(15));
+        assert_eq!(state.get(3, 3), None);
+    }
+
+    #[test]
+    fn test_set_game_state() {
+        let mut state = GameState::default();
+        state.set(3, 3, Some(16));
+        assert_eq!(state.get(3, 3), Some(16));
+        state.set(0, 2, None);
+        assert_eq!(state.get(0, 2), None);
+        // TODO: add more tests
+    }
+
+    const DEFAULT_STATE_STR: &str = "\
+|  1 |  2 |  3 |  4 |
+|  5 |  6 |  7 |  8 |
+|  9 | 10 | 11 | 12 |
+| 13 | 14 | 15 |    |
+";
+
+    #[test]
+    fn test_display_game_state() {
+        let state = GameState::default();
+        assert_eq!(DEFAULT_STATE_STR, format!("{state}"));
+
+        // TODO: add more tests
+    }
+
+    #[test]
+    fn test_compact_string() {
+        let expected = "\
+1  2  3  4
+5  6  7  8
+9  10 11 12
+13 14 15 .
+";
+        assert_eq!(expected, GameState::default().to_compact_string());
+    }
+
+    #[test]
+    fn test_parse_compact_state() {
+        let state = GameState::default();
+        assert_eq!(GameState::from_compact_str(&state.to_compact_string()).unwrap(), state);
+
+        let mut state = GameState::default();
+        state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]);
+        assert_eq!(GameState::from_compact_str(&state.to_compact_string()).unwrap(), state);
+
+        let loose = "\
+ 1   2 3  4
+5 6 7 8
+  9 10   11 12
+
+13 14 15 _
+";
+        assert_eq!(GameState::from_compact_str(loose).unwrap(), GameState::default());
+
+        let wrong0 = "1 2 3\n5 6 7 8\n9 10 11 12\n13 14 15 .\n";
+        let wrong1 = "1 2 3 4\n5 6 7 8\n9 10 11 12\n";
+        let wrong2 = "1 2 3 4\n5 2 7 8\n9 10 11 12\n13 14 15 .\n";
+        let wrong3 = "1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 16 .\n";
+        let wrong4 = "1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 x .\n";
+        assert!(GameState::from_compact_str(wrong0).is_none());
+        assert!(GameState::from_compact_str(wrong1).is_none());
+        assert!(GameState::from_compact_str(wrong2).is_none());
+        assert!(GameState::from_compact_str(wrong3).is_none());
+        assert!(GameState::from_compact_str(wrong4).is_none());
+
+        let mut state = GameState::solved(3);
+        state.perform_moves(&[Move::TopToBottom, Move::LeftToRight]);
+        assert_eq!(state.to_compact_string(), "1 2 3\n4 . 5\n7 8 6\n");
+        assert_eq!(GameState::from_compact_str(&state.to_compact_string()), Some(state));
+        assert!(GameState::from_compact_str("1 2 3\n4 5 6\n7 8 9\n").is_none());
+        assert!(GameState::from_compact_str("").is_none());
+    }
+
+    #[test]
+    fn test_validate_game_state() {
+        let state = GameState::default();
+        assert!(state.all_tiles_unique());
+        let state = corrupted(&state, &[((3, 0), Some(1))]);
+        assert!(!state.all_tiles_unique());
+        let state = corrupted(&state, &[((0, 0), Some(4))]);
+        assert!(state.all_tiles_unique());
+
+        // TODO: add more tests
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut state = GameState::default();
+        assert_eq!(state.get(2, 3), Some(15));
+        assert_eq!(state.get(3, 3), None);
+        state.swap(2, 3, 3, 3);
+        assert!(state.all_tiles_unique());
+        assert_eq!(state.get(2, 3), None);
+        assert_eq!(state.get(3, 3), Some(15));
+
+        state.swap(0, 0, 2, 2);
+        assert!(state.all_tiles_unique());
+        assert_eq!(state.get(0, 0), Some(11));
+
+    }
+
+    #[test]
+    fn test_perform_move() {
+        let mut state = GameState::default();
+        assert!(!state.perform_move(Move::RightToLeft));
+        assert!(!state.perform_move(Move::BottomToTop));
+        assert!(state.perform_move(Move::TopToBottom));
         assert!(state.all_tiles_unique());
+        assert_eq!(state.get(3, 3), Some(12));
+        assert_eq!(state.get(3, 2), None);
+        assert!(state.perform_move(Move::LeftToRight));
+        assert_eq!(state.get(3, 2), Some(11));
+        assert_eq!(state.get(2, 2), None);
+        state.perform_move(Move::LeftToRight);
+        assert_eq!(state.get(2, 2), Some(10));
+        assert_eq!(state.get(1, 2), None);
+    }
+
+    #[test]
+    fn test_game_state_equality() {
+        {
+            let mut state = GameState::default();
+            assert!(!state.perform_move(Move::BottomToTop));
+            assert_eq!(state, GameState::default());
+            assert!(state.perform_move(Move::TopToBottom));
+            let mut state_2 = GameState::default();
+            state_2.swap(3, 3, 3, 2);
+            assert_eq!(state, state_2);
+        }
+  
+        {
+            let mut state = GameState::default();
+            assert!(state.perform_move(Move::LeftToRight));
+            assert!(state.perform_move(Move::RightToLeft));
+            let state_2 = GameState::default();
+            assert_eq!(state, state_2);
+        }
+    }
+
+    #[test]
+    fn test_perform_moves() {
+        { 
+            let mut state = GameState::default();
+            assert_eq!(
+                state.perform_moves(&[Move::RightToLeft, Move::BottomToTop, Move::TopToBottom]),
+                1
+            );
+
+            let mut state = GameState::default();
+            assert_eq!(
+                state.perform_moves(&[Move::TopToBottom, Move::TopToBottom, Move::TopToBottom]),
+                3
+            );
+            let expected = "\
+|  1 |  2 |  3 |    |
+|  5 |  6 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+";
+            assert_eq!(expected, format!("{state}"));
+
+        }
+
+        { 
+            let mut state = GameState::default();
+            assert_eq!(
+                state.perform_moves(&[Move::LeftToRight, Move::LeftToRight, Move::LeftToRight]),
+                3
+            );
+            let expected = "\
+|  1 |  2 |  3 |  4 |
+|  5 |  6 |  7 |  8 |
+|  9 | 10 | 11 | 12 |
+|    | 13 | 14 | 15 |
+";
+            assert_eq!(expected, format!("{state}"));
+
+        }
+
+        { 
+            let mut state = GameState::default();
+            assert_eq!(
+                state.perform_moves(&[Move::LeftToRight, Move::RightToLeft, Move::LeftToRight]),
+                3
+            );
+            let expected = "\
+|  1 |  2 |  3 |  4 |
+|  5 |  6 |  7 |  8 |
+|  9 | 10 | 11 | 12 |
+| 13 | 14 |    | 15 |
+";
+            assert_eq!(expected, format!("{state}"));
+
+        }
+    }
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(
+            GameState::parse_grid(DEFAULT_STATE_STR).unwrap(),
+            GameState::default()
+        );
+
+        let wrong0 = "\
+|  1 | 22 |  3 |    |
+|  5 |  6 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+";
+        let wrong1 = "\
+|  1 |  2 ,  3 |    |
+|  5 |  6 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+";
+        let wrong2 = "\
+|  1 |  2 |  3 |
+|  5 |  6 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+";
+        let wrong3 = "\
+|  1 |  2 |  3 |    |
+|  5 |  6 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+| 13 | 14 | 15 | 12 |
+";
+        let wrong4 = "\
+|  1 |  2 |  3 |    | 1 |
+|  5 |  6 |  7 |  4 | 1 |
+|  9 | 10 | 11 |  8 | 1 |
+| 13 | 14 | 15 | 12 | 1 |
+";
+        let wrong5 = "\
+|  1 |  2 |  3 |    |
+|  5 |  2 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+";
+        assert!(GameState::parse_grid(wrong0).is_none());
+        assert!(GameState::parse_grid(wrong1).is_none());
+        assert!(GameState::parse_grid(wrong2).is_none());
+        assert!(GameState::parse_grid(wrong3).is_none());
+        assert!(GameState::parse_grid(wrong4).is_none());
+        assert!(GameState::parse_grid(wrong5).is_none());
+
+        assert_eq!(DEFAULT_STATE_STR.parse::<GameState>(), Ok(GameState::default()));
+        assert_eq!(GameState::try_from(DEFAULT_STATE_STR), Ok(GameState::default()));
+        assert_eq!(wrong0.parse::<GameState>(), Err(ParseGameStateError));
+        assert_eq!(GameState::try_from(wrong5), Err(ParseGameStateError));
+
+        // TODO: add more tests
+    }
+
+    #[test]
+    fn test_find_shortest_path() {
+        // Test 1: simple path up the board
+        {
+            let expected_moves = [Move::TopToBottom, Move::TopToBottom, Move::TopToBottom];
+            let mut state = GameState::default();
+            assert_eq!(state.perform_moves(&expected_moves), 3);
+
+            let actual_moves = find_shortest_path(GameState::default(), state);
+            assert_eq!(actual_moves.len(), 3);
+            assert_eq!(actual_moves, expected_moves);
+        }
+
+        // Test 2: rotation
+        {
+            let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::BottomToTop];
+            let mut state = GameState::default();
+            assert_eq!(state.perform_moves(&expected_moves), 3);
+
+            let actual_moves = find_shortest_path(GameState::default(), state);
+            assert_eq!(actual_moves.len(), 3);
+            assert_eq!(actual_moves, expected_moves);
+        }
+
+        // Test 3: longer path
+        {
+            let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom];
+            let mut state = GameState::default();
+            assert_eq!(state.perform_moves(&expected_moves), 5);
+
+            let actual_moves = find_shortest_path(GameState::default(), state);
+            assert_eq!(actual_moves.len(), 5);
+            assert_eq!(actual_moves, expected_moves);
+        }
+
+    }
+
+    #[test]
+    fn test_path_cache() {
+        let mut state = GameState::default();
+        state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]);
+
+        let mut cache = PathCache::new();
+        let first = cache.solve(GameState::default(), state.clone());
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+        let second = cache.solve(GameState::default(), state.clone());
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        assert_eq!(first, second);
+        assert_eq!(first, find_shortest_path(GameState::default(), state));
+    }
+
+    #[test]
+    fn test_optimal_distance() {
+        let state = GameState::default();
+        assert_eq!(state.optimal_distance(&GameState::default()), Some(0));
+
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]), 3);
+        assert_eq!(state.optimal_distance(&GameState::default()), Some(3));
+        assert_eq!(GameState::default().optimal_distance(&state), Some(3));
+
+        let mut unsolvable = GameState::default();
+        unsolvable.swap(0, 0, 1, 0);
+        assert_eq!(unsolvable.optimal_distance(&GameState::default()), None);
+    }
+
+    #[test]
+    fn test_random_solvable() {
+        let mut rng = XorShift64::new(42);
+        for _ in 0..200 {
+            let state = GameState::random_solvable(&mut rng);
+            assert!(state.all_tiles_unique());
+            assert!(state.is_solvable());
+        }
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        let top_row = [
+            [Some(1), Some(2), Some(3), Some(4)],
+            [None, None, None, None],
+            [None, None, None, None],
+            [None, None, None, None],
+        ];
+
+        let state = GameState::default();
+        assert!(state.matches_pattern(&top_row));
+
+        let mut state_2 = GameState::default();
+        assert_eq!(state_2.perform_moves(&[Move::TopToBottom, Move::TopToBottom, Move::LeftToRight]), 3);
+        assert_ne!(state, state_2);
+        assert!(state_2.matches_pattern(&top_row));
+
+        let mut state_3 = GameState::default();
+        assert_eq!(state_3.perform_moves(&[Move::TopToBottom, Move::TopToBottom, Move::TopToBottom]), 3);
+        assert!(!state_3.matches_pattern(&top_row));
+
+        let small = GameState::solved(3);
+        assert!(!small.matches_pattern(&top_row));
+        let first_column = [[Some(1), None, None], [Some(4), None, None], [Some(7), None, None]];
+        assert!(small.matches_pattern(&first_column));
+        assert!(!GameState::default().matches_pattern(&first_column));
+        assert!(!small.matches_pattern(&[vec![Some(1), None, None], vec![None, None]]));
+    }
+
+    #[test]
+    fn test_pattern_database() {
+        let goal = GameState::default();
+        let pdb = PatternDatabase::new(&goal, &[&[1, 2, 3], &[5, 6, 9], &[4, 7, 8]]);
+        assert_eq!(pdb.estimate(&goal), 0);
+
+        let mut rng = XorShift64::new(7);
+        for _ in 0..5 {
+            let mut state = GameState::default();
+            for _ in 0..30 {
+                state.perform_move(Move::ALL[(rng.next_u64() % 4) as usize]);
+            }
+
+            let optimal = find_shortest_path_idastar(state.clone(), goal.clone()).unwrap();
+            assert!(pdb.estimate(&state) as usize <= optimal.len());
+            assert!(state.manhattan_distance(&goal) <= pdb.estimate(&state));
+
+            let with_pdb = find_shortest_path_idastar_with(state.clone(), goal.clone(), Some(&pdb)).unwrap();
+            assert_eq!(with_pdb.len(), optimal.len());
+            assert_eq!(state.perform_moves(&with_pdb), with_pdb.len());
+            assert_eq!(state, goal);
+        }
+    }
+
+    #[test]
+    #[ignore = "builds the full 5-5-5 database, which takes about 30s in debug builds"]
+    fn test_pattern_database_555() {
+        let goal = GameState::default();
+        let pdb = PatternDatabase::new_555(&goal);
+        assert_eq!(pdb.estimate(&goal), 0);
+
+        for seed in 0..5 {
+            let mut state = GameState::default();
+            state.scramble_seeded(40, seed);
+
+            let optimal = find_shortest_path_idastar_with(state.clone(), goal.clone(), Some(&pdb)).unwrap();
+            assert!(state.manhattan_distance(&goal) <= pdb.estimate(&state));
+            assert!(pdb.estimate(&state) as usize <= optimal.len());
+            assert_eq!(optimal.len(), find_shortest_path_idastar(state, goal.clone()).unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_blank_position() {
+        let mut state = GameState::default();
+        assert_eq!(state.blank_position(), Some((3, 3)));
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight]), 2);
+        assert_eq!(state.blank_position(), Some((2, 2)));
+        state.set(2, 2, Some(16));
+        assert_eq!(state.blank_position(), None);
+    }
+
+    #[test]
+    fn test_verify_solution() {
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]), 3);
+
+        assert!(state.verify_solution(&[Move::TopToBottom, Move::RightToLeft, Move::BottomToTop]));
+        // moving right at the right edge is not possible, even though skipping it solves the puzzle
+        assert!(!state.verify_solution(&[Move::TopToBottom, Move::RightToLeft, Move::RightToLeft, Move::BottomToTop]));
+        assert!(!state.verify_solution(&[Move::TopToBottom, Move::RightToLeft]));
+        assert!(GameState::default().verify_solution(&[]));
+
+        let mut state = GameState::solved(3);
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight]), 2);
+        assert!(state.is_solvable());
+        assert!(state.verify_solution(&[Move::RightToLeft, Move::BottomToTop]));
+        assert!(!state.verify_solution(&[Move::RightToLeft]));
+        assert!(GameState::solved(3).verify_solution(&[]));
+    }
+
+    #[test]
+    fn test_solved_state() {
+        assert_eq!(GameState::solved(4), GameState::default());
+
+        let state = GameState::solved(3);
+        assert_eq!(state.get(0, 0), Some(1));
+        assert_eq!(state.get(2, 0), Some(3));
+        assert_eq!(state.get(0, 1), Some(4));
+        assert_eq!(state.get(1, 2), Some(8));
+        assert_eq!(state.get(2, 2), None);
+        assert_eq!(state.legal_moves(), vec![Move::LeftToRight, Move::TopToBottom]);
+    }
+
+    #[test]
+    fn test_find_shortest_path_3x3() {
+        let goal = GameState::solved(3);
+        let mut state = goal.clone();
+        let scramble = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom, Move::RightToLeft, Move::BottomToTop];
+        assert_eq!(state.perform_moves(&scramble), scramble.len());
+
+        let moves = find_shortest_path(state.clone(), goal.clone());
+        assert!(moves.len() <= scramble.len());
+        assert_eq!(state.perform_moves(&moves), moves.len());
+        assert_eq!(state, goal);
+    }
+
+    #[test]
+    fn test_is_adjacent() {
+        let mut state = GameState::default();
+        assert!(state.perform_move(Move::TopToBottom));
+        assert_eq!(GameState::default().is_adjacent(&state), Some(Move::TopToBottom));
+        assert_eq!(state.is_adjacent(&GameState::default()), Some(Move::BottomToTop));
+        assert_eq!(state.is_adjacent(&state), None);
+
+        let mut state_2 = GameState::default();
+        assert_eq!(state_2.perform_moves(&[Move::LeftToRight, Move::LeftToRight, Move::TopToBottom]), 3);
+        assert_eq!(state.is_adjacent(&state_2), None);
+    }
+
+    #[test]
+    fn test_perform_moves_strict() {
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves_strict(&[Move::TopToBottom, Move::TopToBottom, Move::TopToBottom]), Ok(()));
+        let mut expected = GameState::default();
+        expected.perform_moves(&[Move::TopToBottom, Move::TopToBottom, Move::TopToBottom]);
+        assert_eq!(state, expected);
+
+        let mut state = GameState::default();
+        assert_eq!(
+            state.perform_moves_strict(&[Move::TopToBottom, Move::RightToLeft, Move::TopToBottom]),
+            Err((1, Move::RightToLeft))
+        );
+        let mut expected = GameState::default();
+        expected.perform_move(Move::TopToBottom);
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn test_cells() {
+        let cells: Vec<(u8, u8, Option<u8>)> = GameState::default().cells().collect();
+        assert_eq!(cells.len(), 16);
+        assert_eq!(cells[0], (0, 0, Some(1)));
+        assert_eq!(cells[1], (0, 1, Some(2)));
+        assert_eq!(cells[4], (1, 0, Some(5)));
+        assert_eq!(cells[15], (3, 3, None));
+        assert_eq!(cells.iter().filter(|(_, _, tile)| tile.is_none()).count(), 1);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]), 3);
+
+        let rotated = state.rotate_cw();
+        assert_eq!(rotated.get(0, 0), Some(13));
+        assert_eq!(rotated.get(3, 0), Some(1));
+        assert_ne!(rotated, state);
+        assert_eq!(rotated.rotate_ccw(), state);
+        assert_eq!(state.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), state);
+        assert_eq!(state.rotate_ccw().rotate_ccw(), state.rotate_cw().rotate_cw());
+    }
+
+    #[test]
+    fn test_mirror() {
+        let state = GameState::default();
+        assert_eq!(state.mirror_horizontal().get(0, 0), Some(4));
+        assert_eq!(state.mirror_horizontal().get(0, 3), None);
+        assert_eq!(state.mirror_vertical().get(0, 0), Some(13));
+        assert_eq!(state.mirror_vertical().get(3, 0), None);
+
+        assert_eq!(state.mirror_horizontal().mirror_horizontal(), state);
+        assert_eq!(state.mirror_vertical().mirror_vertical(), state);
+        assert_eq!(state.mirror_horizontal().mirror_vertical(), state.rotate_cw().rotate_cw());
+    }
+
+    #[test]
+    fn test_canonical() {
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]), 3);
+
+        let canonical = state.canonical();
+        assert_eq!(state.mirror_horizontal().canonical(), canonical);
+        assert_eq!(state.rotate_cw().canonical(), canonical);
+        assert_eq!(state.mirror_vertical().rotate_ccw().canonical(), canonical);
+        assert_eq!(canonical.canonical(), canonical);
+        assert_ne!(GameState::default().canonical(), canonical);
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let goal = GameState::default();
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]), 3);
+        assert_eq!(state.difficulty(&goal), Difficulty::Easy);
+
+        // needs 22 moves
+        let hard = GameState::parse_grid("\
+|    |  2 |  3 |  4 |
+|  1 |  9 | 15 |  7 |
+|  6 | 11 | 10 |  8 |
+|  5 | 13 | 14 | 12 |
+").unwrap();
+        assert_eq!(hard.optimal_distance(&goal), Some(22));
+        assert_eq!(hard.difficulty(&goal), Difficulty::Hard);
+
+        let mut unsolvable = GameState::default();
+        unsolvable.swap(0, 0, 1, 0);
+        assert_eq!(unsolvable.difficulty(&goal), Difficulty::Unsolvable);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_find_shortest_path_parallel() {
+        let test_cases = [
+            vec![Move::TopToBottom, Move::TopToBottom, Move::TopToBottom],
+            vec![Move::TopToBottom, Move::LeftToRight, Move::BottomToTop],
+            vec![Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom],
+            vec![Move::LeftToRight, Move::TopToBottom, Move::RightToLeft, Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::BottomToTop],
+        ];
+        for moves in test_cases {
+            let mut state = GameState::default();
+            assert_eq!(state.perform_moves(&moves), moves.len());
+            assert_eq!(
+                find_shortest_path_parallel(GameState::default(), state.clone()),
+                find_shortest_path(GameState::default(), state)
+            );
+        }
+        assert_eq!(find_shortest_path_parallel(GameState::default(), GameState::default()), vec![]);
+    }
+
+    #[test]
+    fn test_perform_move_3x3() {
+        let mut state = GameState::solved(3);
+        assert!(!state.perform_move(Move::RightToLeft));
+        assert!(!state.perform_move(Move::BottomToTop));
+        assert_eq!(state, GameState::solved(3));
+
+        assert!(state.perform_move(Move::TopToBottom));
+        assert_eq!(state.blank_position(), Some((2, 1)));
+        assert!(!state.perform_move(Move::RightToLeft));
+        assert!(state.perform_move(Move::BottomToTop));
+        assert_eq!(state, GameState::solved(3));
+    }
+
+    #[test]
+    fn test_tile_for_move() {
+        let state = GameState::default();
+        assert_eq!(state.tile_for_move(Move::TopToBottom), Some(12));
+        assert_eq!(state.tile_for_move(Move::LeftToRight), Some(15));
+        assert_eq!(state.tile_for_move(Move::RightToLeft), None);
+        assert_eq!(state.tile_for_move(Move::BottomToTop), None);
+    }
+
+    #[test]
+    fn test_find_shortest_path_is_optimal() {
+        let test_cases = [
+            vec![Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom],
+            vec![Move::LeftToRight, Move::TopToBottom, Move::RightToLeft, Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::BottomToTop],
+        ];
+        for moves in test_cases {
+            let mut state = GameState::default();
+            assert_eq!(state.perform_moves(&moves), moves.len());
+
+            let actual_moves = find_shortest_path(GameState::default(), state.clone());
+            assert_eq!(Some(actual_moves.len()), GameState::default().optimal_distance(&state));
+            assert_eq!(actual_moves.len(), moves.len());
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(GameState::default().validate(), Ok(()));
+        assert_eq!(GameState::solved(3).validate(), Ok(()));
+
+        let state = corrupted(&GameState::default(), &[((3, 0), Some(1))]);
+        assert_eq!(state.validate(), Err(ValidationError::DuplicateTile(1)));
+
+        let state = corrupted(&GameState::default(), &[((3, 3), Some(1))]);
+        assert_eq!(state.validate(), Err(ValidationError::NoBlank));
+
+        let state = corrupted(&GameState::default(), &[((0, 0), None)]);
+        assert_eq!(state.validate(), Err(ValidationError::MultipleBlanks));
+
+        let mut state = GameState::default();
+        state.set(1, 1, Some(16));
+        assert_eq!(state.validate(), Err(ValidationError::TileOutOfRange(16)));
+        state.set(1, 1, Some(0));
+        assert_eq!(state.validate(), Err(ValidationError::TileOutOfRange(0)));
+
+        let mut state = GameState::solved(3);
+        state.set(0, 0, Some(9));
+        assert_eq!(state.validate(), Err(ValidationError::TileOutOfRange(9)));
+
+        let state = GameState{board: vec![vec![Some(1), Some(2)], vec![Some(3), None], vec![Some(4), Some(5)]]};
+        assert_eq!(state.validate(), Err(ValidationError::WrongDimensions));
+        assert!(!state.all_tiles_unique());
+    }
+
+    #[test]
+    fn test_json() {
+        let state = GameState::default();
+        assert_eq!(state.to_json(), "{\"size\":4,\"tiles\":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,0]}");
+        assert_eq!(GameState::from_json(&state.to_json()), Some(state));
+
+        let mut state = GameState::solved(3);
+        state.perform_moves(&[Move::TopToBottom, Move::LeftToRight]);
+        assert_eq!(GameState::from_json(&state.to_json()), Some(state));
+
+        let spaced = "{ \"tiles\": [1, 2, 3, 4, 5, 6, 7, 0, 8],\n  \"size\": 3 }";
+        assert!(GameState::from_json(spaced).is_some());
+
+        assert!(GameState::from_json("").is_none());
+        assert!(GameState::from_json("{\"size\":3}").is_none());
+        assert!(GameState::from_json("{\"size\":3,\"tiles\":[1,2,3,4,5,6,7,0,8],}").is_none());
+        assert!(GameState::from_json("{\"size\":3,\"tiles\":[1,2,3,4,5,6,7,0,8]").is_none());
+        assert!(GameState::from_json("{\"size\":3,\"tiles\":[1,2,3,4,5,6,7,0,x]}").is_none());
+        assert!(GameState::from_json("{\"size\":3,\"tiles\":[1,2,3,4,5,6,7,0,8],\"extra\":1}").is_none());
+        // duplicate tile
+        assert!(GameState::from_json("{\"size\":3,\"tiles\":[1,2,3,4,5,6,7,0,7]}").is_none());
+        // wrong number of tiles
+        assert!(GameState::from_json("{\"size\":4,\"tiles\":[1,2,3,4,5,6,7,0,8]}").is_none());
+    }
+
+    #[test]
+    fn test_find_shortest_path_traced() {
+        let mut state = GameState::default();
+        assert!(state.perform_move(Move::TopToBottom));
+        let (moves, traced) = find_shortest_path_traced(GameState::default(), state.clone()).unwrap();
+        assert_eq!(moves, [Move::TopToBottom]);
+        // the start, the state after LeftToRight, and the goal
+        assert_eq!(traced.len(), 3);
+        assert_eq!(traced[0], (GameState::default(), None));
+        assert_eq!(traced[2], (state, Some(GameState::default())));
+
+        let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom];
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&expected_moves), 5);
+        let (moves, traced) = find_shortest_path_traced(GameState::default(), state.clone()).unwrap();
+        assert_eq!(moves, find_shortest_path(GameState::default(), state.clone()));
+
+        // no duplicates, and every parent was discovered before its child and is one move away
+        let distinct: HashSet<GameState> = traced.iter().map(|(state, _)| state.clone()).collect();
+        assert_eq!(distinct.len(), traced.len());
+        for (i, (state, parent)) in traced.iter().enumerate().skip(1) {
+            let parent = parent.as_ref().unwrap();
+            assert!(traced[..i].iter().any(|(earlier, _)| earlier == parent));
+            assert!(parent.is_adjacent(state).is_some());
+        }
+
+        // BFS visits every state closer than the goal, and some of the states as far as the goal
+        let mut layers = vec![HashSet::from([GameState::default()])];
+        for _ in 0..5 {
+            let next: HashSet<GameState> = layers.last().unwrap().iter()
+                .flat_map(|state| state.neighbors().into_iter().map(|(_, next)| next))
+                .filter(|next| layers.iter().all(|layer| !layer.contains(next)))
+                .collect();
+            layers.push(next);
+        }
+        let closer: usize = layers[..5].iter().map(|layer| layer.len()).sum();
+        assert!(traced.len() > closer);
+        assert!(traced.len() <= closer + layers[5].len());
+        assert_eq!(traced.last().unwrap().0, state);
+
+        let mut unsolvable = GameState::default();
+        unsolvable.swap_tiles(1, 2);
+        assert_eq!(find_shortest_path_traced(GameState::default(), unsolvable), None);
+    }
+
+    #[test]
+    fn test_perform_move_torus() {
+        let mut state = GameState::default();
+        assert!(!state.perform_move_mode(Move::RightToLeft, MoveMode::Standard));
+        assert_eq!(state, GameState::default());
+
+        assert!(state.perform_move_mode(Move::RightToLeft, MoveMode::Torus));
+        assert_eq!(state.get(3, 3), Some(13));
+        assert_eq!(state.get(0, 3), None);
+        assert!(state.perform_move_mode(Move::LeftToRight, MoveMode::Torus));
+        assert_eq!(state, GameState::default());
+
+        assert!(state.perform_move_mode(Move::BottomToTop, MoveMode::Torus));
+        assert_eq!(state.get(3, 3), Some(4));
+        assert_eq!(state.get(3, 0), None);
+
+        // moves away from the edges behave the same in both modes
+        let mut standard = GameState::default();
+        let mut torus = GameState::default();
+        assert!(standard.perform_move_mode(Move::TopToBottom, MoveMode::Standard));
+        assert!(torus.perform_move_mode(Move::TopToBottom, MoveMode::Torus));
+        assert_eq!(standard, torus);
+    }
+
+    #[test]
+    fn test_pack() {
+        let state = GameState::default();
+        assert_eq!(state.pack(), 0x0FED_CBA9_8765_4321);
+        assert_eq!(GameState::unpack(state.pack()), Some(state));
+
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::BottomToTop]), 3);
+        assert_eq!(GameState::unpack(state.pack()), Some(state.clone()));
+
+        let mut state_2 = GameState::default();
+        state_2.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::RightToLeft, Move::LeftToRight, Move::BottomToTop]);
+        assert_eq!(state, state_2);
+        assert_eq!(state.pack(), state_2.pack());
+        assert_ne!(state.pack(), GameState::default().pack());
+
+        // every tile twice
+        assert_eq!(GameState::unpack(0x1111_1111_1111_1111), None);
+    }
+
+    #[test]
+    fn test_solve_tile() {
+        let goal = GameState::default();
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::BottomToTop, Move::LeftToRight]), 6);
+
+        for tile in 1..=15 {
+            let moves = state.solve_tile(tile, &goal);
+            let mut solved = state.clone();
+            assert_eq!(solved.perform_moves(&moves), moves.len());
+            assert_eq!(solved.position_of(tile), goal.position_of(tile));
+        }
+
+        assert_eq!(goal.solve_tile(5, &goal), vec![]);
+        assert_eq!(goal.solve_tile(16, &goal), vec![]);
+        assert!(!state.solve_tile(10, &goal).is_empty());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut up = GameState::default();
+        assert!(up.perform_move(Move::TopToBottom));
+        let mut left = GameState::default();
+        assert!(left.perform_move(Move::LeftToRight));
+        let mut up_left = up.clone();
+        assert!(up_left.perform_move(Move::LeftToRight));
+
+        // up:      1 2 3 4 / 5 6 7 8 / 9 10 11 0 / ...
+        // up_left: 1 2 3 4 / 5 6 7 8 / 9 10 0 11 / ...
+        // left:    1 2 3 4 / ... / 13 14 0 15
+        // default: 1 2 3 4 / ... / 13 14 15 0
+        let states = std::collections::BTreeSet::from([GameState::default(), up.clone(), left.clone(), up_left.clone(), up.clone()]);
+        let sorted: Vec<GameState> = states.into_iter().collect();
+        assert_eq!(sorted, vec![up_left, up, left, GameState::default()]);
+        assert_eq!(GameState::default().cmp(&GameState::default()), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_scramble_seeded() {
+        let mut state = GameState::default();
+        let mut state_2 = GameState::default();
+        let moves = state.scramble_seeded(30, 1234);
+        assert_eq!(moves.len(), 30);
+        assert_eq!(state_2.scramble_seeded(30, 1234), moves);
+        assert_eq!(state, state_2);
+
+        let mut replayed = GameState::default();
+        assert_eq!(replayed.perform_moves(&moves), 30);
+        assert_eq!(replayed, state);
+
+        let mut state_3 = GameState::default();
+        assert_ne!(state_3.scramble_seeded(30, 4321), moves);
+    }
+
+    #[test]
+    fn test_find_shortest_path_astar() {
+        let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom];
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&expected_moves), 5);
+        assert_eq!(find_shortest_path_astar(GameState::default(), state.clone()).unwrap().len(), 5);
+        assert_eq!(find_shortest_path_astar(GameState::default(), GameState::default()), Some(vec![]));
+
+        let mut unsolvable = GameState::default();
+        unsolvable.swap(0, 0, 1, 0);
+        assert_eq!(find_shortest_path_astar(GameState::default(), unsolvable), None);
+    }
+
+    #[test]
+    fn test_compare_heuristics() {
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom]), 5);
+
+        let results = compare_heuristics(state, GameState::default());
+        assert_eq!(results.len(), 2);
+        let (manhattan_name, manhattan_estimate, manhattan_expanded) = results[0];
+        let (misplaced_name, misplaced_estimate, misplaced_expanded) = results[1];
+        assert_eq!(manhattan_name, "manhattan distance");
+        assert_eq!(misplaced_name, "misplaced tiles");
+        assert_eq!(manhattan_estimate, 5);
+        assert_eq!(misplaced_estimate, 5);
+        assert!(manhattan_expanded > 0);
+        assert!(manhattan_expanded <= misplaced_expanded);
+    }
+
+    #[test]
+    fn test_hardest_state_within() {
+        let goal = GameState::default();
+        let (state, distance) = hardest_state_within(&goal, 8);
+        assert_eq!(distance, 8);
+        assert_eq!(state.optimal_distance(&goal), Some(8));
+
+        assert_eq!(hardest_state_within(&goal, 0), (goal.clone(), 0));
+
+        // the 2x2 puzzle only has 12 reachable states, the farthest one 6 moves away
+        let small_goal = GameState::solved(2);
+        let (state, distance) = hardest_state_within(&small_goal, 100);
+        assert_eq!(distance, 6);
+        assert_eq!(state.optimal_distance(&small_goal), Some(6));
+    }
+
+    #[test]
+    fn test_replay() {
+        let moves = [Move::TopToBottom, Move::LeftToRight, Move::BottomToTop];
+        let frames: Vec<GameState> = GameState::default().replay(&moves).collect();
+        assert_eq!(frames.len(), 3);
+        let mut state = GameState::default();
+        assert!(state.perform_move(Move::TopToBottom));
+        assert_eq!(frames[0], state);
+        assert_eq!(state.perform_moves(&moves[1..]), 2);
+        assert_eq!(frames[2], state);
+
+        // the illegal RightToLeft is skipped
+        let frames: Vec<GameState> = GameState::default().replay(&[Move::TopToBottom, Move::RightToLeft, Move::BottomToTop]).collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1], GameState::default());
+    }
+
+    #[test]
+    fn test_display_alternate() {
+        let expected = "\
+┌────┬────┬────┬────┐
+│  1 │  2 │  3 │  4 │
+├────┼────┼────┼────┤
+│  5 │  6 │  7 │  8 │
+├────┼────┼────┼────┤
+│  9 │ 10 │ 11 │ 12 │
+├────┼────┼────┼────┤
+│ 13 │ 14 │ 15 │    │
+└────┴────┴────┴────┘
+";
+        let state = GameState::default();
+        assert_eq!(format!("{:#}", state), expected);
+        assert_eq!(format!("{}", state), DEFAULT_STATE_STR);
+    }
+
+    #[test]
+    fn test_tiles_in_place() {
+        let goal = GameState::default();
+        assert_eq!(goal.tiles_in_place(&goal), 15);
+
+        let mut state = GameState::default();
+        assert!(state.perform_move(Move::TopToBottom));
+        assert_eq!(state.tiles_in_place(&goal), 14);
+        assert!(state.perform_move(Move::LeftToRight));
+        assert_eq!(state.tiles_in_place(&goal), 13);
+    }
+
+    #[test]
+    fn test_swap_tiles() {
+        let mut state = GameState::default();
+        assert!(state.is_solvable());
+        assert!(state.swap_tiles(14, 15));
+        assert_eq!(state.get(1, 3), Some(15));
+        assert_eq!(state.get(2, 3), Some(14));
+        assert!(!state.is_solvable());
+        assert!(state.swap_tiles(1, 7));
+        assert!(state.is_solvable());
+
+        let before = state.clone();
+        assert!(!state.swap_tiles(1, 16));
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn test_find_path_greedy() {
+        let goal = GameState::default();
+        let mut rng = XorShift64::new(99);
+        for _ in 0..5 {
+            let state = GameState::random_solvable(&mut rng);
+            let moves = find_path_greedy(state.clone(), goal.clone()).unwrap();
+            assert!(state.verify_solution(&moves));
+        }
+
+        let mut unsolvable = GameState::default();
+        unsolvable.swap_tiles(1, 2);
+        assert_eq!(find_path_greedy(unsolvable, goal.clone()), None);
+        assert_eq!(find_path_greedy(goal.clone(), goal), Some(vec![]));
+    }
+
+    #[test]
+    fn test_display_large_board() {
+        let expected = "\
+|  1 |  2 |  3 |  4 |  5 |
+|  6 |  7 |  8 |  9 | 10 |
+| 11 | 12 | 13 | 14 | 15 |
+| 16 | 17 | 18 | 19 | 20 |
+| 21 | 22 | 23 | 24 |    |
+";
+        assert_eq!(format!("{}", GameState::solved(5)), expected);
+
+        let expected = "\
+| 1 | 2 | 3 |
+| 4 | 5 | 6 |
+| 7 | 8 |   |
+";
+        assert_eq!(format!("{}", GameState::solved(3)), expected);
+
+        let expected = "\
+|   1 |   2 |   3 |   4 |   5 |   6 |   7 |   8 |   9 |  10 |  11 |
+";
+        assert!(format!("{}", GameState::solved(11)).starts_with(expected));
+    }
+
+    #[test]
+    fn test_permutation() {
+        let state = GameState::default();
+        assert_eq!(state.as_permutation(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0]);
+        assert_eq!(GameState::from_permutation(&state.as_permutation()), Some(state));
+
+        let mut state = GameState::solved(3);
+        assert_eq!(state.perform_moves(&[Move::TopToBottom, Move::LeftToRight]), 2);
+        assert_eq!(state.as_permutation(), vec![1, 2, 3, 4, 0, 5, 7, 8, 6]);
+        assert_eq!(GameState::from_permutation(&state.as_permutation()), Some(state));
+
+        assert_eq!(GameState::from_permutation(&[1, 2, 3, 4, 5, 6, 7, 0]), None);
+        assert_eq!(GameState::from_permutation(&[1, 2, 3, 4, 5, 6, 7, 0, 7]), None);
+        assert_eq!(GameState::from_permutation(&[]), None);
+    }
+
+    #[test]
+    fn test_scramble_distance_histogram() {
+        let histogram = scramble_distance_histogram(20, 12, 5);
+        assert_eq!(histogram.iter().map(|(_, count)| count).sum::<usize>(), 20);
+        assert!(histogram.iter().all(|(distance, _)| *distance <= 12));
+        // scrambles have the parity of their length
+        assert!(histogram.iter().all(|(distance, _)| distance % 2 == 0));
+        assert!(histogram.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(scramble_distance_histogram(20, 12, 5), histogram);
+    }
+
+    #[test]
+    fn test_find_shortest_path_equal_states() {
+        assert_eq!(find_shortest_path(GameState::default(), GameState::default()), vec![]);
+        assert_eq!(find_shortest_path_idastar(GameState::default(), GameState::default()), Some(vec![]));
+        assert_eq!(find_shortest_path_astar(GameState::default(), GameState::default()), Some(vec![]));
+        assert_eq!(find_path_greedy(GameState::default(), GameState::default()), Some(vec![]));
+        assert_eq!(find_shortest_path_traced(GameState::default(), GameState::default()).unwrap().0, vec![]);
+
+        let mut state = GameState::solved(3);
+        state.scramble_seeded(10, 3);
+        assert_eq!(find_shortest_path(state.clone(), state), vec![]);
+    }
+
+    #[test]
+    fn test_parse_moves() {
+        assert_eq!(parse_moves("UDLR"), Ok(vec![Move::BottomToTop, Move::TopToBottom, Move::RightToLeft, Move::LeftToRight]));
+        assert_eq!(parse_moves(" d d\nl "), Ok(vec![Move::TopToBottom, Move::TopToBottom, Move::RightToLeft]));
+        assert_eq!(parse_moves(""), Ok(vec![]));
+        assert_eq!(parse_moves("UDX"), Err(ParseMoveError { index: 2, found: 'X' }));
+    }
+
+    #[test]
+    fn test_apply_str() {
+        let mut state = GameState::default();
+        assert_eq!(state.apply_str("DDD"), Ok(3));
+        let expected = "\
+|  1 |  2 |  3 |    |
+|  5 |  6 |  7 |  4 |
+|  9 | 10 | 11 |  8 |
+| 13 | 14 | 15 | 12 |
+";
+        assert_eq!(expected, format!("{state}"));
+
+        // the blank is in the top row, so the tile above it can't slide down
+        assert_eq!(state.apply_str("DR"), Ok(1));
+
+        let before = state.clone();
+        assert_eq!(state.apply_str("R?"), Err(ParseMoveError { index: 1, found: '?' }));
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn test_goal_position() {
+        assert_eq!(goal_position(15, 4), (2, 3));
+        assert_eq!(goal_position(1, 4), (0, 0));
+        assert_eq!(goal_position(0, 4), (3, 3));
+        assert_eq!(goal_position(4, 3), (0, 1));
+
+        let goal = GameState::solved(5);
+        for tile in 1..25 {
+            let (x, y) = goal_position(tile, 5);
+            assert_eq!(goal.get(x, y), Some(tile));
+        }
+    }
+
+    #[test]
+    fn test_from_str_strict() {
+        let state = GameState::parse_grid(DEFAULT_STATE_STR).unwrap();
+        assert_eq!(GameState::from_str_strict(&state.to_string()), Some(state));
+        let solved = GameState::solved(3);
+        assert_eq!(GameState::from_str_strict(&solved.to_string()), Some(solved));
+
+        let ragged = "\
+|  1 |  2 | 3 |  4 |
+|  5 |  6 |  7 |  8 |
+|  9 | 10 | 11 | 12 |
+| 13 | 14 | 15 |    |
+";
+        assert!(GameState::parse_grid(ragged).is_some());
+        assert_eq!(GameState::from_str_strict(ragged), None);
+        assert_eq!(GameState::from_str_strict(DEFAULT_STATE_STR.trim_end()), None);
+        assert_eq!(GameState::from_str_strict(""), None);
+    }
+
+    #[test]
+    fn test_tile_distance() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.perform_move(Move::LeftToRight);
+
+        for tile in 1..16 {
+            let expected = if tile == 15 { 1 } else { 0 };
+            assert_eq!(state.tile_distance(tile, &goal), Some(expected));
+        }
+        assert_eq!(state.tile_distance(0, &goal), None);
+        assert_eq!(state.tile_distance(16, &goal), None);
+    }
+
+    #[test]
+    fn test_core_without_std() {
+        // only uses what is available without the std feature; `cargo test --no-default-features`
+        // runs it against the ordered fallback collections
+        let mut state = GameState::solved(3);
+        assert_eq!(state.apply_str("RD"), Ok(2));
+        assert_eq!(state.manhattan_distance(&GameState::solved(3)), 2);
+        assert_eq!(find_shortest_path(state.clone(), GameState::solved(3)), vec![Move::BottomToTop, Move::RightToLeft]);
+
+        let pdb = PatternDatabase::new(&GameState::solved(3), &[&[1, 2, 3, 4], &[5, 6, 7, 8]]);
+        assert_eq!(pdb.estimate(&state), 2);
+        assert_eq!(GameState::from_str_strict(&state.to_string()), Some(state));
+    }
+
+    #[test]
+    fn test_try_swap() {
+        let mut state = GameState::default();
+        assert_eq!(state.try_swap(0, 0, 3, 3), Ok(()));
+        assert_eq!(state.get(0, 0), None);
+        assert_eq!(state.get(3, 3), Some(1));
+
+        let before = state.clone();
+        assert_eq!(state.try_swap(0, 0, 4, 1), Err(OutOfBounds { x: 4, y: 1 }));
+        assert_eq!(state.try_swap(1, 4, 0, 0), Err(OutOfBounds { x: 1, y: 4 }));
+        assert_eq!(state, before);
+
+        let mut small = GameState::solved(3);
+        assert_eq!(small.try_swap(3, 0, 0, 0), Err(OutOfBounds { x: 3, y: 0 }));
+    }
+
+    #[test]
+    fn test_find_path_weighted_astar() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(40, 7);
+        let optimal = find_shortest_path_astar(state.clone(), goal.clone()).unwrap();
+
+        let path = find_path_weighted_astar(state.clone(), goal.clone(), 1.0);
+        assert_eq!(path.len(), optimal.len());
+        assert!(state.verify_solution(&path));
+
+        let path = find_path_weighted_astar(state.clone(), goal.clone(), 3.0);
+        assert!(path.len() >= optimal.len());
+        assert!(state.verify_solution(&path));
+
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        assert!(find_path_weighted_astar(unsolvable, goal, 3.0).is_empty());
+    }
+
+    #[test]
+    fn test_inverse_state() {
+        let goal = GameState::default();
+        let inverse = goal.inverse_state();
+        assert_eq!(inverse.as_permutation(), vec![15, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(inverse.inverse_state(), goal);
+
+        let mut state = goal.clone();
+        state.scramble_seeded(30, 3);
+        assert_ne!(state.inverse_state(), state);
+        assert_eq!(state.inverse_state().inverse_state(), state);
+
+        let small = GameState::solved(3);
+        assert_eq!(small.inverse_state().inverse_state(), small);
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let mut moved = GameState::default();
+        moved.perform_move(Move::LeftToRight);
+        let malformed = "\
+|  1 |  2 |  3 |  4 |
+|  5 |  6 |  7 |  8 |
+| 13 | 14 | 15 |    |
+";
+        let file = format!("\n{DEFAULT_STATE_STR}\n{malformed}\n\n  \n{moved}");
+
+        let boards = GameState::parse_many(&file);
+        assert_eq!(boards, vec![Ok(GameState::default()), Err(1), Ok(moved)]);
+        assert!(GameState::parse_many("").is_empty());
+    }
+
+    #[test]
+    fn test_neighbor_for() {
+        let state = GameState::default();
+        let mut moved = state.clone();
+        moved.perform_move(Move::TopToBottom);
+        assert_eq!(state.neighbor_for(Move::TopToBottom), Some(moved));
+        assert_eq!(state.neighbor_for(Move::BottomToTop), None);
+        assert_eq!(state.neighbor_for(Move::RightToLeft), None);
+
+        for (m, neighbor) in state.neighbors() {
+            assert_eq!(state.neighbor_for(m), Some(neighbor));
+        }
+    }
+
+    #[test]
+    fn test_find_all_shortest_paths() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(10, 27);
+        let shortest = find_shortest_path(state.clone(), goal.clone()).len();
+
+        let paths = find_all_shortest_paths(state.clone(), goal.clone());
+        assert_eq!(paths.len(), 2);
+        assert_ne!(paths[0], paths[1]);
+        for path in &paths {
+            assert_eq!(path.len(), shortest);
+            assert!(state.verify_solution(path));
+        }
+
+        assert_eq!(find_all_shortest_paths(goal.clone(), goal.clone()), vec![vec![]]);
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        assert!(find_all_shortest_paths(unsolvable, goal).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_find_shortest_path_deadline() {
+        use std::time::{Duration, Instant};
+
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(10, 5);
+        assert_eq!(find_shortest_path_deadline(state.clone(), goal.clone(), Instant::now()), None);
+
+        let path = find_shortest_path_deadline(state.clone(), goal.clone(), Instant::now() + Duration::from_secs(60)).unwrap();
+        assert_eq!(Some(path.len()), state.optimal_distance(&goal));
+        assert!(state.verify_solution(&path));
+    }
+
+    #[test]
+    fn test_move_name_and_arrow() {
+        assert_eq!(Move::LeftToRight.name(), "slide right");
+        assert_eq!(Move::RightToLeft.name(), "slide left");
+        assert_eq!(Move::TopToBottom.name(), "slide down");
+        assert_eq!(Move::BottomToTop.name(), "slide up");
+
+        assert_eq!(Move::LeftToRight.arrow(), '→');
+        assert_eq!(Move::RightToLeft.arrow(), '←');
+        assert_eq!(Move::TopToBottom.arrow(), '↓');
+        assert_eq!(Move::BottomToTop.arrow(), '↑');
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let state: GameState = (0..16u8)
+            .map(|i| ((i % 4, i / 4), if i == 15 { None } else { Some(i + 1) }))
+            .collect();
+        assert_eq!(state, GameState::default());
+
+        let state: GameState = [((0, 1), Some(3)), ((0, 0), Some(1)), ((1, 0), Some(2))].into_iter().collect();
+        assert_eq!(state.get(1, 1), None);
+        assert_eq!(state.validate(), Ok(()));
+        assert_eq!(state, GameState::solved(2));
+
+        let state: GameState = [((0, 0), Some(1))].into_iter().collect();
+        assert_eq!(state.validate(), Err(ValidationError::WrongDimensions));
+    }
+
+    #[test]
+    fn test_inversion_count() {
+        let mut state = GameState::default();
+        assert_eq!(state.inversion_count(), 0);
+        state.swap_tiles(14, 15);
+        assert_eq!(state.inversion_count(), 1);
+
+        // horizontal moves keep the order of the tiles
+        let mut state = GameState::default();
+        state.perform_moves(&[Move::LeftToRight, Move::LeftToRight]);
+        assert_eq!(state.inversion_count(), 0);
+        // moving 12 down puts it in front of 13, 14 and 15
+        state.perform_moves(&[Move::RightToLeft, Move::RightToLeft, Move::TopToBottom]);
+        assert_eq!(state.inversion_count(), 3);
+    }
+
+    #[test]
+    fn test_from_date() {
+        let state = GameState::from_date(2024, 3, 14);
+        assert_eq!(state, GameState::from_date(2024, 3, 14));
+        assert!(state.is_solvable());
+        assert_ne!(state, GameState::default());
+
+        assert_ne!(state, GameState::from_date(2024, 3, 15));
+        assert_ne!(state, GameState::from_date(2024, 4, 14));
+        assert_ne!(state, GameState::from_date(2025, 3, 14));
+    }
+
+    #[test]
+    fn test_simplify_moves() {
+        let moves = parse_moves("LRUDU").unwrap();
+        assert_eq!(simplify_moves(&moves), vec![Move::BottomToTop]);
+        assert_eq!(simplify_moves(&parse_moves("LUDR").unwrap()), vec![]);
+        assert_eq!(simplify_moves(&parse_moves("LLUU").unwrap()), parse_moves("LLUU").unwrap());
+
+        // the empty position in the middle, so that every move is possible
+        let mut start = GameState::default();
+        start.apply_str("DDRR").unwrap();
+        let mut original = start.clone();
+        assert_eq!(original.perform_moves(&moves), moves.len());
+        let mut simplified = start.clone();
+        simplified.perform_moves(&simplify_moves(&moves));
+        assert_eq!(original, simplified);
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let svg = GameState::default().to_svg(50);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"200\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<svg").count(), 1);
+        assert_eq!(svg.matches("<text").count(), 15);
+        for tile in 1..16 {
+            assert!(svg.contains(&format!(">{tile}</text>")));
+        }
+
+        assert_eq!(GameState::solved(3).to_svg(10).matches("<text").count(), 8);
+    }
+
+    #[test]
+    fn test_slide_tile() {
+        let mut state = GameState::default();
+        assert!(state.slide_tile(12));
+        let mut expected = GameState::default();
+        expected.perform_move(Move::TopToBottom);
+        assert_eq!(state, expected);
+
+        assert!(!state.slide_tile(1));
+        assert!(!state.slide_tile(15));
+        assert!(!state.slide_tile(0));
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn test_slide_row_to_blank() {
+        let mut state = GameState::default();
+        assert_eq!(state.slide_row_to_blank(13), 3);
+        let expected = "\
+|  1 |  2 |  3 |  4 |
+|  5 |  6 |  7 |  8 |
+|  9 | 10 | 11 | 12 |
+|    | 13 | 14 | 15 |
+";
+        assert_eq!(expected, format!("{state}"));
+
+        assert_eq!(state.slide_row_to_blank(1), 3);
+        let expected = "\
+|    |  2 |  3 |  4 |
+|  1 |  6 |  7 |  8 |
+|  5 | 10 | 11 | 12 |
+|  9 | 13 | 14 | 15 |
+";
+        assert_eq!(expected, format!("{state}"));
+
+        let before = state.clone();
+        assert_eq!(state.slide_row_to_blank(6), 0);
+        assert_eq!(state.slide_row_to_blank(16), 0);
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn test_token() {
+        let mut state = GameState::default();
+        state.scramble_seeded(30, 11);
+        let token = state.to_token();
+        assert_eq!(token.len(), 11);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(GameState::from_token(&token), Some(state));
+        assert_eq!(GameState::from_token(&GameState::default().to_token()), Some(GameState::default()));
+
+        // the first character holds parts of the first two tiles, so this leaves a duplicate
+        let corrupted = format!("{}{}", if token.starts_with('A') { 'B' } else { 'A' }, &token[1..]);
+        assert_eq!(GameState::from_token(&corrupted), None);
+        assert_eq!(GameState::from_token(&token[1..]), None);
+        assert_eq!(GameState::from_token(&format!("{}!", &token[..10])), None);
+    }
+
+    #[test]
+    fn test_parity() {
+        let state = GameState::default();
+        assert_eq!(state.parity(), Parity::Even);
+        assert_eq!(GameState::solved(3).parity(), Parity::Even);
+        for (_, neighbor) in state.neighbors() {
+            assert_eq!(neighbor.parity(), Parity::Even);
+        }
+
+        let mut scrambled = state.clone();
+        scrambled.scramble_seeded(25, 4);
+        assert_eq!(scrambled.parity(), Parity::Even);
+        scrambled.swap_tiles(3, 9);
+        assert_eq!(scrambled.parity(), Parity::Odd);
+        assert!(!scrambled.is_solvable());
+    }
+
+    #[test]
+    fn test_find_shortest_path_restricted() {
+        let goal = GameState::default();
+        let allowed = [Move::RightToLeft, Move::TopToBottom, Move::BottomToTop];
+
+        let mut vertical = goal.clone();
+        vertical.perform_moves(&[Move::TopToBottom, Move::TopToBottom]);
+        assert_eq!(find_shortest_path_restricted(vertical, goal.clone(), &allowed), Some(vec![Move::BottomToTop, Move::BottomToTop]));
+
+        // solving this needs the empty position to move left again
+        let mut cycled = goal.clone();
+        cycled.perform_moves(&[Move::LeftToRight, Move::TopToBottom, Move::RightToLeft, Move::BottomToTop]);
+        assert_eq!(find_shortest_path_restricted(cycled.clone(), goal.clone(), &allowed), None);
+        assert_eq!(find_shortest_path_restricted(cycled, goal.clone(), &Move::ALL).map(|path| path.len()), Some(4));
+
+        assert_eq!(find_shortest_path_restricted(goal.clone(), goal, &[]), Some(vec![]));
+    }
+
+    #[test]
+    fn test_duplicate_tiles() {
+        let state = GameState::default();
+        assert!(state.duplicate_tiles().is_empty());
+
+        let state = corrupted(&state, &[((2, 0), Some(2))]);
+        assert_eq!(state.duplicate_tiles(), vec![2]);
+        let state = corrupted(&state, &[((0, 0), None), ((3, 0), Some(9))]);
+        assert_eq!(state.duplicate_tiles(), vec![0, 2, 9]);
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let state = GameState::from_grid([
+            [Some(1), Some(2), Some(3), Some(4)],
+            [Some(5), Some(6), Some(7), Some(8)],
+            [Some(9), Some(10), Some(11), Some(12)],
+            [Some(13), Some(14), Some(15), None],
+        ]);
+        assert_eq!(state, Some(GameState::default()));
+
+        let state = GameState::from_grid([
+            [Some(1), Some(2), Some(3), Some(4)],
+            [Some(5), Some(6), Some(7), Some(8)],
+            [Some(9), Some(10), Some(11), Some(12)],
+            [Some(13), Some(14), Some(14), None],
+        ]);
+        assert_eq!(state, None);
+    }
+
+    #[test]
+    fn test_move_delta() {
+        assert_eq!(Move::LeftToRight.delta(), (0, -1));
+        assert_eq!(Move::RightToLeft.delta(), (0, 1));
+        assert_eq!(Move::TopToBottom.delta(), (-1, 0));
+        assert_eq!(Move::BottomToTop.delta(), (1, 0));
+
+        // the empty position in the middle, so that every move is possible
+        let mut start = GameState::default();
+        start.apply_str("DR").unwrap();
+        let (x, y) = start.blank_position().unwrap();
+        for m in Move::ALL {
+            let mut state = start.clone();
+            assert!(state.perform_move(m));
+            let (row_delta, column_delta) = m.delta();
+            assert_eq!(state.blank_position(), Some(((x as i8 + column_delta) as u8, (y as i8 + row_delta) as u8)));
+        }
+    }
+
+    #[test]
+    fn test_idastar_unsolvable() {
+        let mut unsolvable = GameState::default();
+        unsolvable.scramble_seeded(60, 2);
+        unsolvable.swap_tiles(1, 2);
+        assert_eq!(find_shortest_path_idastar(unsolvable.clone(), GameState::default()), None);
+        assert_eq!(unsolvable.optimal_distance(&GameState::default()), None);
+    }
+
+    #[test]
+    fn test_distance_matrix() {
+        let goal = GameState::default();
+        assert_eq!(goal.distance_matrix(&goal), [[0; 4]; 4]);
+
+        let mut state = goal.clone();
+        state.apply_str("DDRR").unwrap();
+        let matrix = state.distance_matrix(&goal);
+        assert_eq!(matrix[1][3], 1);
+        assert_eq!(matrix[2][2], 0);
+        assert_eq!(matrix[3][3], 1);
+        assert_eq!(matrix.iter().flatten().sum::<u32>(), state.manhattan_distance(&goal));
+
+        state.scramble_seeded(30, 8);
+        let matrix = state.distance_matrix(&goal);
+        assert!(matrix.iter().flatten().any(|distance| *distance > 0));
+        assert_eq!(matrix.iter().flatten().sum::<u32>(), state.manhattan_distance(&goal));
+    }
+
+    #[test]
+    fn test_manhattan_components() {
+        let goal = GameState::default();
+        assert_eq!(goal.manhattan_components(&goal), (0, 0));
+
+        let mut state = goal.clone();
+        state.apply_str("RRR").unwrap();
+        assert_eq!(state.manhattan_components(&goal), (0, 3));
+
+        state.apply_str("DD").unwrap();
+        assert_eq!(state.manhattan_components(&goal), (2, 3));
+        assert_eq!(state.manhattan_distance(&goal), 5);
+    }
+
+    #[test]
+    fn test_labeled_tiles() {
+        let rows = |s: &str| -> Vec<Vec<Option<char>>> {
+            s.lines().map(|line| line.chars().map(|c| if c == '.' { None } else { Some(c) }).collect()).collect()
+        };
+        let goal = GameState::from_rows(rows("CAT\nDOG\nSU.")).unwrap();
+        assert_eq!(goal.get(2, 0), Some('T'));
+        assert_eq!(goal.relabel(&goal), Some(GameState::solved(3)));
+        assert!(GameState::from_rows(rows("CAT\nDOG\nS..")).is_none());
+        assert!(GameState::from_rows(rows("CAT\nDOG\nSA.")).is_none());
+
+        let mut state = goal.clone();
+        assert_eq!(state.perform_moves(&parse_moves("DRDLU").unwrap()), 5);
+        assert_ne!(state, goal);
+        assert_eq!(state.tile_for_move(Move::TopToBottom), Some('O'));
+
+        let path = find_shortest_path_labeled(state.clone(), goal.clone()).unwrap();
+        assert_eq!(path.len(), 5);
+        state.perform_moves(&path);
+        assert_eq!(state, goal);
+
+        let other = GameState::from_rows(rows("CAT\nDOG\nSX.")).unwrap();
+        assert_eq!(find_shortest_path_labeled(other, goal), None);
+    }
+
+    #[test]
+    fn test_best_hint() {
+        let goal = GameState::default();
+        assert_eq!(goal.best_hint(&goal), None);
+
+        let mut state = goal.clone();
+        state.scramble_seeded(20, 9);
+        let hint = state.best_hint(&goal).unwrap();
+        let distance = state.manhattan_distance(&goal);
+        assert!(state.perform_move(hint));
+        assert!(state.manhattan_distance(&goal) < distance);
+
+        let mut state = goal.clone();
+        state.perform_move(Move::TopToBottom);
+        assert_eq!(state.best_hint(&goal), Some(Move::BottomToTop));
+    }
+
+    #[test]
+    fn test_tracked_state() {
+        let goal = GameState::default();
+        let mut tracked = TrackedState::new(goal.clone(), &goal);
+        assert_eq!(tracked.manhattan_distance(), 0);
+        assert!(!tracked.perform_move(Move::RightToLeft));
+
+        let mut rng = XorShift64::new(13);
+        for _ in 0..200 {
+            let m = Move::ALL[(rng.next_u64() % 4) as usize];
+            let moved = tracked.state().legal_moves().contains(&m);
+            assert_eq!(tracked.perform_move(m), moved);
+            assert_eq!(tracked.manhattan_distance(), tracked.state().manhattan_distance(&goal));
+        }
+        assert_ne!(tracked.state(), &goal);
+    }
+
+    #[test]
+    fn test_reachable() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(40, 6);
+        assert!(reachable(&state, &goal));
+        assert!(reachable(&goal, &state));
+        state.swap_tiles(5, 6);
+        assert!(!reachable(&state, &goal));
+        assert!(!reachable(&GameState::solved(3), &goal));
+
+        // compare with every state a search visits on the 2x2 board
+        let goal = GameState::solved(2);
+        let mut visited = HashSet::from([goal.clone()]);
+        let mut queue = VecDeque::from([goal.clone()]);
+        while let Some(state) = queue.pop_front() {
+            for (_, next) in state.neighbors() {
+                if visited.insert(next.clone()) { queue.push_back(next); }
+            }
+        }
+        assert_eq!(visited.len(), 12);
+        let mut permutations = 0;
+        for tiles in 0..256u32 {
+            let tiles: Vec<u8> = (0..4).map(|i| (tiles >> (2 * i) & 3) as u8).collect();
+            if let Some(state) = GameState::from_permutation(&tiles) {
+                assert_eq!(reachable(&state, &goal), visited.contains(&state));
+                permutations += 1;
+            }
+        }
+        assert_eq!(permutations, 24);
+    }
+
+    #[test]
+    fn test_to_labeled_string() {
+        // a string continuation would strip the leading spaces of the header
+        let expected = "     0    1    2    3
+0 |  1 |  2 |  3 |  4 |
+1 |  5 |  6 |  7 |  8 |
+2 |  9 | 10 | 11 | 12 |
+3 | 13 | 14 | 15 |    |
+";
+        assert_eq!(GameState::default().to_labeled_string(), expected);
+
+        let labeled = GameState::solved(11).to_labeled_string();
+        assert!(labeled.starts_with("       0     1"));
+        assert!(labeled.lines().next().unwrap().ends_with("    10"));
+        assert!(labeled.lines().nth(1).unwrap().starts_with(" 0 |   1 |"));
+        assert!(labeled.lines().last().unwrap().starts_with("10 | 111 |"));
+    }
+
+    #[test]
+    fn test_can_move() {
+        let state = GameState::default();
+        assert!(!state.can_move(Move::RightToLeft));
+        assert!(!state.can_move(Move::BottomToTop));
+        assert!(state.can_move(Move::TopToBottom));
+        assert!(state.can_move(Move::LeftToRight));
+        assert_eq!(state, GameState::default());
+
+        for m in Move::ALL {
+            let mut moved = state.clone();
+            assert_eq!(moved.perform_move(m), state.can_move(m));
+        }
+    }
+
+    #[test]
+    fn test_solve_session() {
+        let mut initial = GameState::default();
+        initial.apply_str("DDR").unwrap();
+
+        let mut session = SolveSession::new(initial.clone());
+        assert!(session.record(Move::RightToLeft));
+        assert!(!session.record(Move::RightToLeft));
+        assert!(session.record(Move::BottomToTop));
+        assert!(session.record(Move::BottomToTop));
+        assert_eq!(session.moves, vec![Move::RightToLeft, Move::BottomToTop, Move::BottomToTop]);
+        assert_eq!(session.final_state(), GameState::default());
+        assert_eq!(session.initial, initial);
+    }
 
-        // TODO: add more tests
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_solve_session_serde() {
+        let mut session = SolveSession::new(GameState::solved(3));
+        session.record(Move::TopToBottom);
+        session.record(Move::LeftToRight);
+
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: SolveSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, session);
+        assert_eq!(restored.final_state(), session.final_state());
     }
 
     #[test]
-    fn test_swap() {
+    fn test_blank_distance_to() {
         let mut state = GameState::default();
-        assert_eq!(state.get(2, 3), Some(15));
-        assert_eq!(state.get(3, 3), None);
-        state.swap(2, 3, 3, 3);
-        assert!(state.all_tiles_unique());
-        assert_eq!(state.get(2, 3), None);
-        assert_eq!(state.get(3, 3), Some(15));
+        assert_eq!(state.blank_distance_to(0, 0), 6);
+        assert_eq!(state.blank_distance_to(3, 3), 0);
+        assert_eq!(state.blank_distance_to(3, 0), 3);
 
-        state.swap(0, 0, 2, 2);
-        assert!(state.all_tiles_unique());
-        assert_eq!(state.get(0, 0), Some(11));
+        state.apply_str("DR").unwrap();
+        assert_eq!(state.blank_distance_to(0, 0), 4);
+        assert_eq!(state.blank_distance_to(3, 3), 2);
+    }
+
+    #[test]
+    fn test_find_shortest_path_to_any() {
+        let start = GameState::default();
+        let mut near = start.clone();
+        near.apply_str("DD").unwrap();
+        let mut far = start.clone();
+        far.apply_str("RRRD").unwrap();
+        let mut unsolvable = start.clone();
+        unsolvable.swap_tiles(1, 2);
 
+        let (index, path) = find_shortest_path_to_any(start.clone(), &[far.clone(), unsolvable, near.clone()]);
+        assert_eq!(index, 2);
+        assert_eq!(path, vec![Move::TopToBottom, Move::TopToBottom]);
+
+        assert_eq!(find_shortest_path_to_any(start.clone(), &[far.clone()]).1.len(), 4);
+        assert_eq!(find_shortest_path_to_any(start.clone(), &[near.clone(), start.clone()]), (1, vec![]));
+        assert_eq!(find_shortest_path_to_any(start, &[near.clone(), near]).0, 0);
     }
 
     #[test]
-    fn test_perform_move() {
+    fn test_stable_hash() {
+        let state = GameState::default();
+        assert_eq!(state.pack(), 0x0FED_CBA9_8765_4321);
+        assert_eq!(state.stable_hash(), 0x0840_dbdd_c4aa_eaa5);
+
+        let mut moved = state.clone();
+        moved.perform_move(Move::TopToBottom);
+        assert_ne!(moved.stable_hash(), state.stable_hash());
+        assert_eq!(moved.stable_hash(), moved.clone().stable_hash());
+    }
+
+    #[test]
+    fn test_move_blank() {
         let mut state = GameState::default();
-        assert!(!state.perform_move(Move::RightToLeft));
-        assert!(!state.perform_move(Move::BottomToTop));
-        assert!(state.perform_move(Move::TopToBottom));
-        assert!(state.all_tiles_unique());
+        assert!(!state.move_blank(Direction::Down));
+        assert!(!state.move_blank(Direction::Right));
+        assert!(state.move_blank(Direction::Up));
+        assert_eq!(state.blank_position(), Some((3, 2)));
         assert_eq!(state.get(3, 3), Some(12));
-        assert_eq!(state.get(3, 2), None);
-        assert!(state.perform_move(Move::LeftToRight));
-        assert_eq!(state.get(3, 2), Some(11));
-        assert_eq!(state.get(2, 2), None);
-        state.perform_move(Move::LeftToRight);
-        assert_eq!(state.get(2, 2), Some(10));
-        assert_eq!(state.get(1, 2), None);
+        assert!(state.move_blank(Direction::Left));
+        assert_eq!(state.blank_position(), Some((2, 2)));
+
+        assert!(state.move_blank(Direction::Up));
+        assert!(state.move_blank(Direction::Up));
+        assert!(!state.move_blank(Direction::Up));
+        assert_eq!(state.blank_position(), Some((2, 0)));
     }
 
     #[test]
-    fn test_game_state_equality() {
-        {
-            let mut state = GameState::default();
-            assert!(!state.perform_move(Move::BottomToTop));
-            assert_eq!(state, GameState::default());
-            assert!(state.perform_move(Move::TopToBottom));
-            let mut state_2 = GameState::default();
-            state_2.set(3, 3, Some(12));
-            state_2.set(3, 2, None);
-            assert_eq!(state, state_2);
+    fn test_movable_tiles() {
+        let mut state = GameState::default();
+        assert_eq!(state.movable_tiles(), vec![12, 15]);
+        state.apply_str("DR").unwrap();
+        assert_eq!(state.movable_tiles(), vec![7, 10, 11, 15]);
+        for tile in state.movable_tiles() {
+            assert!(state.clone().slide_tile(tile));
         }
-  
-        {
-            let mut state = GameState::default();
-            assert!(state.perform_move(Move::LeftToRight));
-            assert!(state.perform_move(Move::RightToLeft));
-            let state_2 = GameState::default();
-            assert_eq!(state, state_2);
+    }
+
+    #[test]
+    fn test_solve_states() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(8, 10);
+
+        let states: Vec<GameState> = solve_states(state.clone(), goal.clone()).collect();
+        assert_eq!(states.first(), Some(&state));
+        assert_eq!(states.last(), Some(&goal));
+        assert_eq!(states.len(), find_shortest_path(state.clone(), goal.clone()).len() + 1);
+        for pair in states.windows(2) {
+            assert!(pair[0].is_adjacent(&pair[1]).is_some());
         }
+
+        assert_eq!(solve_states(goal.clone(), goal.clone()).collect::<Vec<_>>(), vec![goal.clone()]);
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        assert_eq!(solve_states(unsolvable, goal).count(), 0);
     }
 
     #[test]
-    fn test_perform_moves() {
-        { 
-            let mut state = GameState::default();
-            assert_eq!(
-                state.perform_moves(&[Move::RightToLeft, Move::BottomToTop, Move::TopToBottom]),
-                1
-            );
+    fn test_symmetries() {
+        let state = GameState::default();
+        assert_eq!(state.symmetries(), vec![Symmetry::Identity]);
+        assert_eq!(state.transform(Symmetry::RotateCw), state.rotate_cw());
+        assert_eq!(state.transform(Symmetry::RotateCcw), state.rotate_ccw());
+        assert_eq!(state.transform(Symmetry::MirrorHorizontal), state.mirror_horizontal());
+        assert_eq!(state.transform(Symmetry::MirrorVertical), state.mirror_vertical());
+        assert_eq!(state.transform(Symmetry::Rotate180), state.rotate_cw().rotate_cw());
+        assert_eq!(state.transform(Symmetry::MirrorDiagonal), state.rotate_cw().mirror_horizontal());
+        assert_eq!(state.transform(Symmetry::MirrorAntiDiagonal), state.rotate_ccw().mirror_horizontal());
 
-            let mut state = GameState::default();
-            assert_eq!(
-                state.perform_moves(&[Move::TopToBottom, Move::TopToBottom, Move::TopToBottom]),
-                3
-            );
-            let expected = "\
-|  1 |  2 |  3 |    |
-|  5 |  6 |  7 |  4 |
-|  9 | 10 | 11 |  8 |
-| 13 | 14 | 15 | 12 |
-";
-            assert_eq!(expected, format!("{state}"));
+        // 1 2 1
+        // 3 4 3
+        // 5 _ 5
+        let mirrored = corrupted(&GameState::solved(3), &[
+            ((2, 0), Some(1)), ((1, 0), Some(2)), ((0, 1), Some(3)), ((1, 1), Some(4)),
+            ((2, 1), Some(3)), ((0, 2), Some(5)), ((1, 2), None), ((2, 2), Some(5)),
+        ]);
+        assert_eq!(mirrored.symmetries(), vec![Symmetry::Identity, Symmetry::MirrorHorizontal]);
+
+        let mut tiles: Vec<((u8, u8), Option<u8>)> = [(0, 0), (1, 0), (2, 0), (0, 1), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .into_iter().map(|position| (position, Some(1))).collect();
+        tiles.push(((1, 1), None));
+        let blank = corrupted(&GameState::solved(3), &tiles);
+        assert_eq!(blank.symmetries(), Symmetry::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_perform_moves_detailed() {
+        let mut state = GameState::default();
+        let results = state.perform_moves_detailed(&[Move::TopToBottom, Move::RightToLeft, Move::LeftToRight]);
+        assert_eq!(results, vec![true, false, true]);
+
+        let mut expected = GameState::default();
+        expected.perform_moves(&[Move::TopToBottom, Move::LeftToRight]);
+        assert_eq!(state, expected);
+        assert!(state.perform_moves_detailed(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_find_path_min_blank_travel() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(12, 5);
+        let shortest = find_shortest_path(state.clone(), goal.clone());
+
+        let path = find_path_min_blank_travel(state.clone(), goal.clone());
+        assert_eq!(path.len(), shortest.len());
+        assert!(state.verify_solution(&path));
+
+        // horizontal moves are expensive, so the path may get longer but never more expensive
+        let cost = |m: Move| if matches!(m, Move::LeftToRight | Move::RightToLeft) { 5 } else { 1 };
+        let total = |path: &[Move]| path.iter().map(|m| cost(*m)).sum::<u32>();
+        let path = find_path_min_cost(state.clone(), goal.clone(), cost).unwrap();
+        assert!(state.verify_solution(&path));
+        assert!(total(&path) <= total(&shortest));
+
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        assert!(find_path_min_blank_travel(unsolvable.clone(), goal.clone()).is_empty());
+        assert_eq!(find_path_min_cost(unsolvable, goal, cost), None);
+    }
+
+    #[test]
+    fn test_moves_to_key_events() {
+        let moves = [Move::TopToBottom; 3];
+        assert_eq!(moves_to_key_events(&moves), vec!['↑', '↑', '↑']);
+        assert_eq!(moves_to_key_events(&[Move::LeftToRight, Move::BottomToTop]), vec!['←', '↓']);
 
+        let mut expected = GameState::default();
+        expected.perform_moves(&moves);
+        let mut state = GameState::default();
+        for key in moves_to_key_events(&moves) {
+            let dir = [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+                .into_iter().find(|dir| dir.arrow() == key).unwrap();
+            assert!(state.move_blank(dir));
         }
+        assert_eq!(state, expected);
+    }
 
-        { 
-            let mut state = GameState::default();
-            assert_eq!(
-                state.perform_moves(&[Move::LeftToRight, Move::LeftToRight, Move::LeftToRight]),
-                3
-            );
-            let expected = "\
-|  1 |  2 |  3 |  4 |
-|  5 |  6 |  7 |  8 |
-|  9 | 10 | 11 | 12 |
-|    | 13 | 14 | 15 |
-";
-            assert_eq!(expected, format!("{state}"));
+    #[test]
+    fn test_build_distance_table() {
+        let goal = GameState::solved(3);
+        let table = build_distance_table(&goal);
+        assert_eq!(table.len(), 181440);
+        assert_eq!(table.max_distance(), 31);
+        assert_eq!(goal.optimal_distance_from_table(&table), Some(0));
 
+        for seed in 0..5 {
+            let mut state = goal.clone();
+            state.scramble_seeded(30, seed);
+            let distance = state.optimal_distance_from_table(&table).map(usize::from);
+            assert_eq!(distance, state.optimal_distance(&goal));
         }
 
-        { 
-            let mut state = GameState::default();
-            assert_eq!(
-                state.perform_moves(&[Move::LeftToRight, Move::RightToLeft, Move::LeftToRight]),
-                3
-            );
-            let expected = "\
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        assert_eq!(unsolvable.optimal_distance_from_table(&table), None);
+        assert_eq!(GameState::default().optimal_distance_from_table(&table), None);
+    }
+
+    #[test]
+    fn test_from_loose_str() {
+        let goal = GameState::default();
+        let boxed = "\
++----+----+----+----+
 |  1 |  2 |  3 |  4 |
++----+----+----+----+
 |  5 |  6 |  7 |  8 |
++----+----+----+----+
 |  9 | 10 | 11 | 12 |
-| 13 | 14 |    | 15 |
++----+----+----+----+
+| 13 | 14 | 15 |    |
++----+----+----+----+
 ";
-            assert_eq!(expected, format!("{state}"));
+        assert_eq!(GameState::from_loose_str(boxed), Some(goal.clone()));
+        assert_eq!(GameState::from_loose_str(DEFAULT_STATE_STR), Some(goal.clone()));
+        assert_eq!(GameState::from_loose_str("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 X"), Some(goal.clone()));
+        assert_eq!(GameState::from_loose_str("1-2-3-4\n5-6-7-8\n9-10-11-12\n13-14-15-0\n"), Some(goal.clone()));
+        assert_eq!(GameState::from_loose_str("  1 2 3\n  4 5 6\n  7 8 _\n"), Some(GameState::solved(3)));
 
-        }
+        assert_eq!(GameState::from_loose_str("1 2 3\n4 5 6\n7 8\n"), None);
+        assert_eq!(GameState::from_loose_str("1 2 3\n4 5 6\n7 7 _\n"), None);
+        assert_eq!(GameState::from_loose_str(""), None);
     }
 
     #[test]
-    fn test_parse_state() {
-        assert_eq!(
-            GameState::from_str(DEFAULT_STATE_STR).unwrap(),
-            GameState::default()
-        );
+    fn test_find_shortest_path_with_progress() {
+        let goal = GameState::default();
+        let mut state = goal.clone();
+        state.scramble_seeded(10, 3);
 
-        let wrong0 = "\
-|  1 | 22 |  3 |    |
-|  5 |  6 |  7 |  4 |
-|  9 | 10 | 11 |  8 |
-| 13 | 14 | 15 | 12 |
-";
-        let wrong1 = "\
-|  1 |  2 ,  3 |    |
-|  5 |  6 |  7 |  4 |
-|  9 | 10 | 11 |  8 |
-| 13 | 14 | 15 | 12 |
-";
-        let wrong2 = "\
-|  1 |  2 |  3 |
-|  5 |  6 |  7 |  4 |
-|  9 | 10 | 11 |  8 |
-| 13 | 14 | 15 | 12 |
-";
-        let wrong3 = "\
-|  1 |  2 |  3 |    |
-|  5 |  6 |  7 |  4 |
-|  9 | 10 | 11 |  8 |
-| 13 | 14 | 15 | 12 |
-| 13 | 14 | 15 | 12 |
-";
-        let wrong4 = "\
-|  1 |  2 |  3 |    | 1 |
-|  5 |  6 |  7 |  4 | 1 |
-|  9 | 10 | 11 |  8 | 1 |
-| 13 | 14 | 15 | 12 | 1 |
-";
-        let wrong5 = "\
-|  1 |  2 |  3 |    |
-|  5 |  2 |  7 |  4 |
-|  9 | 10 | 11 |  8 |
-| 13 | 14 | 15 | 12 |
-";
-        assert!(GameState::from_str(wrong0).is_none());
-        assert!(GameState::from_str(wrong1).is_none());
-        assert!(GameState::from_str(wrong2).is_none());
-        assert!(GameState::from_str(wrong3).is_none());
-        assert!(GameState::from_str(wrong4).is_none());
-        assert!(GameState::from_str(wrong5).is_none());
+        let mut layers = vec![];
+        let path = find_shortest_path_with_progress(state.clone(), goal.clone(), |depth, frontier| layers.push((depth, frontier))).unwrap();
+        assert_eq!(path.len(), find_shortest_path(state.clone(), goal.clone()).len());
+        assert!(state.verify_solution(&path));
 
-        // TODO: add more tests
+        let depths: Vec<usize> = layers.iter().map(|(depth, _)| *depth).collect();
+        assert_eq!(depths, (1..=path.len()).collect::<Vec<usize>>());
+        assert!(layers.iter().all(|(_, frontier)| *frontier > 0));
+
+        let mut calls = 0;
+        assert_eq!(find_shortest_path_with_progress(goal.clone(), goal.clone(), |_, _| calls += 1), Some(vec![]));
+        assert_eq!(calls, 0);
     }
 
     #[test]
-    fn test_find_shortest_path() {
-        // Test 1: simple path up the board
-        {
-            let expected_moves = [Move::TopToBottom, Move::TopToBottom, Move::TopToBottom];
-            let mut state = GameState::default();
-            assert_eq!(state.perform_moves(&expected_moves), 3);
+    fn test_disorder_score() {
+        let goal = GameState::default();
+        assert_eq!(goal.disorder_score(&goal), 0.0);
 
-            let actual_moves = find_shortest_path(GameState::default(), state);
-            assert_eq!(actual_moves.len(), 3);
-            assert_eq!(actual_moves, expected_moves);
-        }
+        let mut state = goal.clone();
+        state.perform_move(Move::LeftToRight);
+        assert_eq!(state.disorder_score(&goal), 1.0 / 15.0);
 
-        // Test 2: rotation
-        {
-            let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::BottomToTop];
+        state.scramble_seeded(50, 9);
+        let score = state.disorder_score(&goal);
+        assert!(score > 0.0);
+        assert_eq!(score, state.manhattan_distance(&goal) as f64 / 15.0);
+    }
+
+    #[test]
+    fn test_winning_moves() {
+        let goal = GameState::default();
+        assert!(goal.winning_moves(&goal).is_empty());
+
+        let mut state = goal.clone();
+        state.perform_move(Move::TopToBottom);
+        assert_eq!(state.winning_moves(&goal), vec![Move::BottomToTop]);
+
+        state.perform_move(Move::LeftToRight);
+        assert!(state.winning_moves(&goal).is_empty());
+    }
+
+    #[test]
+    fn test_transpose() {
+        let state = GameState::default();
+        let transposed = state.transpose();
+        assert_eq!(transposed.get(1, 0), Some(5));
+        assert_eq!(transposed.get(0, 1), Some(2));
+        assert_eq!(transposed.get(3, 3), None);
+        assert_eq!(transposed.transpose(), state);
+        assert_eq!(transposed, state.transform(Symmetry::MirrorDiagonal));
+
+        let mut scrambled = state.clone();
+        scrambled.scramble_seeded(20, 4);
+        assert_eq!(scrambled.transpose().transpose(), scrambled);
+    }
+
+    #[test]
+    fn test_blank_symbol() {
+        let zero_blank = "| 1 | 2 | 3 |\n| 4 | 5 | 6 |\n| 7 | 8 | 0 |\n";
+        assert_eq!(GameState::from_str_with_blank(zero_blank, "0"), Some(GameState::solved(3)));
+        assert_eq!(GameState::from_str_with_blank(zero_blank, ""), None);
+        assert_eq!(GameState::from_str_with_blank(DEFAULT_STATE_STR, ""), Some(GameState::default()));
+        let sixteen_blank = "1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 16\n";
+        assert_eq!(GameState::from_str_with_blank(sixteen_blank, "16"), Some(GameState::default()));
+        assert_eq!(GameState::from_str_with_blank("1 2 3\n4 x 6\n7 8 x\n", "x"), None);
+
+        let state = GameState::default();
+        assert_eq!(state.to_string_with(&DisplayConfig::default()), state.to_string());
+        let config = DisplayConfig{blank: String::from("x")};
+        assert!(state.to_string_with(&config).ends_with("| 13 | 14 | 15 |  x |\n"));
+        let config = DisplayConfig{blank: String::from("0")};
+        assert_eq!(GameState::solved(3).to_string_with(&config), zero_blank);
+        assert_eq!(GameState::from_str_with_blank(&state.to_string_with(&config), "0"), Some(state));
+    }
+
+    #[test]
+    fn test_estimate_solve_difficulty() {
+        let goal = GameState::default();
+        assert_eq!(estimate_solve_difficulty(&goal, &goal), DifficultyEstimate::Fast);
+
+        let mut near = goal.clone();
+        near.perform_moves(&[Move::TopToBottom, Move::LeftToRight]);
+        assert_eq!(estimate_solve_difficulty(&near, &goal), DifficultyEstimate::Fast);
+
+        let mut far = goal.clone();
+        far.scramble_seeded(200, 1);
+        assert!(far.manhattan_distance(&goal) >= 20);
+        assert_eq!(estimate_solve_difficulty(&far, &goal), DifficultyEstimate::Slow);
+
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        assert_eq!(estimate_solve_difficulty(&unsolvable, &goal), DifficultyEstimate::Unsolvable);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = XorShift64::new(11);
+        for _ in 0..20 {
             let mut state = GameState::default();
-            assert_eq!(state.perform_moves(&expected_moves), 3);
+            state.scramble_seeded(15, rng.next_u64());
+            // scrambling a copy gives a random sequence of moves that are possible from the state
+            let sequence = state.clone().scramble_seeded(30, rng.next_u64());
+            assert!(state.round_trip(&sequence));
+        }
 
-            let actual_moves = find_shortest_path(GameState::default(), state);
-            assert_eq!(actual_moves.len(), 3);
-            assert_eq!(actual_moves, expected_moves);
+        // the RightToLeft move is not possible, so it is skipped rather than undone
+        let state = GameState::default();
+        assert!(state.round_trip(&[Move::TopToBottom, Move::RightToLeft, Move::TopToBottom]));
+        assert!(state.round_trip(&[]));
+    }
+
+    #[test]
+    fn test_find_shortest_path_with_cost() {
+        // Test 3 of test_find_shortest_path
+        let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom];
+        let mut state = GameState::default();
+        assert_eq!(state.perform_moves(&expected_moves), 5);
+
+        let (moves, cost) = find_shortest_path_with_cost(GameState::default(), state);
+        assert_eq!(moves, expected_moves);
+        assert_eq!(cost, moves.len());
+        assert_eq!(find_shortest_path_with_cost(GameState::default(), GameState::default()), (vec![], 0));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "a tile appears more than once")]
+    fn test_debug_assert_invariants() {
+        GameState::default().debug_assert_invariants();
+        GameState::solved(3).debug_assert_invariants();
+
+        let mut state = corrupted(&GameState::default(), &[((0, 0), Some(2))]);
+        state.perform_move(Move::TopToBottom);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "a tile appears more than once")]
+    fn test_set_checks_invariants() {
+        GameState::default().set(0, 0, Some(2));
+    }
+
+    #[test]
+    fn test_invariants_fuzz() {
+        // random sequences of moves, including impossible ones, on boards of different sizes;
+        // every swap checks the invariants in debug builds, and validate checks them again
+        let mut rng = XorShift64::new(2024);
+        for _ in 0..200 {
+            let size = 2 + (rng.next_u64() % 4) as usize;
+            let mut state = GameState::solved(size);
+            for _ in 0..(rng.next_u64() % 100) {
+                let m = Move::ALL[(rng.next_u64() % 4) as usize];
+                let before = state.clone();
+                if state.perform_move(m) {
+                    assert!(state.round_trip(&[m.inverse()]));
+                } else {
+                    assert_eq!(state, before);
+                }
+                state.debug_assert_invariants();
+            }
+            assert_eq!(state.validate(), Ok(()));
+            assert!(state.can_reach(&GameState::solved(size)));
         }
+    }
 
-        // Test 3: longer path
-        {
-            let expected_moves = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom];
-            let mut state = GameState::default();
-            assert_eq!(state.perform_moves(&expected_moves), 5);
+    #[test]
+    fn test_goal_string() {
+        assert_eq!(goal_string(4), DEFAULT_STATE_STR);
+        for size in 2..=5 {
+            assert_eq!(goal_string(size), GameState::solved(size).to_string());
+        }
+    }
 
-            let actual_moves = find_shortest_path(GameState::default(), state);
-            assert_eq!(actual_moves.len(), 5);
-            assert_eq!(actual_moves, expected_moves);
+    #[test]
+    fn test_idastar_max_threshold() {
+        // can_reach would reject these right away, so go through the deepening loop directly;
+        // without the cap it would raise the threshold forever
+        let goal = GameState::solved(2);
+        let mut unsolvable = goal.clone();
+        unsolvable.swap_tiles(1, 2);
+        let heuristic = |state: &GameState| state.manhattan_distance(&goal);
+        assert_eq!(idastar_deepen(unsolvable.clone(), &goal, MAX_OPTIMAL_MOVES, &heuristic), None);
+
+        // the cap only stops thresholds beyond it, solutions right at the cap are still found
+        let mut state = goal.clone();
+        state.perform_moves(&[Move::TopToBottom, Move::LeftToRight]);
+        let padded = |s: &GameState| if *s == goal { 0 } else { MAX_OPTIMAL_MOVES - 1 };
+        assert_eq!(idastar_deepen(state.clone(), &goal, MAX_OPTIMAL_MOVES, &padded).map(|path| path.len()), Some(2));
+        assert_eq!(idastar_deepen(state, &goal, MAX_OPTIMAL_MOVES - 1, &padded), None);
+    }
+
+    #[test]
+    fn test_inverse_move_pruning() {
+        // Test 3 of test_find_shortest_path, and a longer scramble
+        let mut test_case_3 = GameState::default();
+        test_case_3.perform_moves(&[Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::LeftToRight, Move::TopToBottom]);
+        let mut scrambled = GameState::default();
+        scrambled.scramble_seeded(12, 8);
+
+        for state in [test_case_3, scrambled] {
+            let (pruned, pruned_generated) = breadth_first_search(GameState::default(), state.clone(), true);
+            let (unpruned, unpruned_generated) = breadth_first_search(GameState::default(), state, false);
+            // the same path, with far fewer states generated: every state but the start has
+            // one neighbor that undoes the move leading to it
+            assert_eq!(pruned, unpruned);
+            assert!(pruned_generated * 4 < unpruned_generated * 3, "{pruned_generated} vs {unpruned_generated}");
         }
+    }
+
+    #[test]
+    fn test_solvers_on_large_boards() {
+        // too large to be packed, so the solvers remember the tiles instead
+        let goal = GameState::solved(5);
+        let mut state = goal.clone();
+        let scramble = [Move::TopToBottom, Move::LeftToRight, Move::LeftToRight, Move::BottomToTop];
+        assert_eq!(state.perform_moves(&scramble), 4);
 
+        let path = find_shortest_path(state.clone(), goal.clone());
+        assert_eq!(path.len(), 4);
+        assert_eq!(find_shortest_path_astar(state.clone(), goal.clone()).map(|path| path.len()), Some(4));
+        assert_eq!(find_shortest_path_traced(state.clone(), goal.clone()).map(|(path, _)| path), Some(path.clone()));
+        assert_eq!(find_all_shortest_paths(state.clone(), goal.clone()), vec![path.clone()]);
+        assert_eq!(find_shortest_path_to_any(state.clone(), core::slice::from_ref(&goal)), (0, path));
+
+        let mut moved = state.clone();
+        moved.perform_moves(&find_path_greedy(state, goal.clone()).unwrap());
+        assert_eq!(moved, goal);
+    }
+
+    #[test]
+    fn test_estimate_solve_difficulty_boundaries() {
+        assert_eq!(estimated_expansions(0), 1);
+        assert_eq!(estimated_expansions(1), 2);
+        assert_eq!(estimated_expansions(2), 5);
+        assert_eq!(estimated_expansions(12), 8721);
+        assert_eq!(estimated_expansions(13), 18575);
+        assert_eq!(estimated_expansions(18), 814391);
+        assert_eq!(estimated_expansions(19), 1734652);
+        assert_eq!(estimated_expansions(200), u64::MAX);
+
+        // real boards on both sides of the boundaries
+        let goal = GameState::default();
+        let categories: Vec<(u32, DifficultyEstimate)> = (0..60)
+            .map(|seed| {
+                let mut state = goal.clone();
+                state.scramble_seeded(8 + seed as usize % 20, seed);
+                (state.manhattan_distance(&goal), estimate_solve_difficulty(&state, &goal))
+            })
+            .collect();
+        for (distance, category) in categories {
+            let expected = match distance {
+                0..=12 => DifficultyEstimate::Fast,
+                13..=18 => DifficultyEstimate::Moderate,
+                _ => DifficultyEstimate::Slow,
+            };
+            assert_eq!(category, expected, "distance {distance}");
+        }
     }
 }